@@ -0,0 +1,68 @@
+use specs::*;
+use state::*;
+use input::Direction;
+
+fn bestiary_rows(entries: &Vec<SpiritType>) -> Vec<Vec<usize>> {
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        let same_row = match rows.last() {
+            Some(row) => entries[row[0]].element_index() == entry.element_index(),
+            None => false,
+        };
+        if same_row {
+            rows.last_mut().unwrap().push(idx);
+        } else {
+            rows.push(vec![idx]);
+        }
+    }
+    rows
+}
+
+fn move_in_bestiary(index: usize, direction: &Direction) -> usize {
+    let entries = all_spirit_types();
+    let rows = bestiary_rows(&entries);
+    let (row, column) = rows.iter().enumerate()
+        .filter_map(|(row, indices)| indices.iter().position(|idx| *idx == index).map(|column| (row, column)))
+        .next()
+        .unwrap_or((0, 0));
+    let row = match direction {
+        Direction::Up => (row + rows.len() - 1) % rows.len(),
+        Direction::Down => (row + 1) % rows.len(),
+        _ => row,
+    };
+    let column = match direction {
+        Direction::Left => (column + rows[row].len() - 1) % rows[row].len(),
+        Direction::Right => (column + 1) % rows[row].len(),
+        _ => column,
+    };
+    rows[row][column.min(rows[row].len() - 1)]
+}
+
+pub struct HandleBestiary;
+impl<'a> System<'a> for HandleBestiary {
+    type SystemData = (
+        WriteExpect<'a, PlayState>,
+        WriteExpect<'a, InputState>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (mut play_state, mut input_state, sounds): Self::SystemData) {
+        match (play_state.clone(), input_state.clone()) {
+            (PlayState::MainMenu(3), InputState::Select) => {
+                sounds.play(&sounds.confirm);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Bestiary(0);
+            },
+            (PlayState::Bestiary(index), InputState::Move(direction)) => {
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Bestiary(move_in_bestiary(index, &direction));
+            },
+            (PlayState::Bestiary(_), InputState::Escape) | (PlayState::Bestiary(_), InputState::Select) => {
+                sounds.play(&sounds.cancel);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::MainMenu(3);
+            },
+            _ => {}
+        }
+    }
+}
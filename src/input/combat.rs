@@ -32,10 +32,50 @@ impl<'a> System<'a> for HandleBattleMenu {
         ReadExpect<'a, PlayState>,
         WriteExpect<'a, InputState>,
         WriteExpect<'a, BattleState>,
+        ReadStorage<'a, Bag>,
+        ReadStorage<'a, Spirit>,
+        ReadStorage<'a, PlayerSpirit>,
         ReadExpect<'a, Sounds>,
+        Write<'a, BattleLog>,
     );
-    fn run(&mut self, (play_state, mut input_state, mut battle_state, sounds): Self::SystemData) {
+    fn run(&mut self, (play_state, mut input_state, mut battle_state, bags, spirits, player_spirits, sounds, mut battle_log): Self::SystemData) {
+        let mut items = Vec::new();
+        for bag in (&bags).join() {
+            items = bag.battle_items();
+        }
+        let enemy_count = (&spirits, !&player_spirits).join().count();
         match (play_state.clone(), input_state.clone(), battle_state.retreating, battle_state.notifying()) {
+            (PlayState::InBattle, InputState::Lock, false, _) => {
+                if battle_log.open {
+                    sounds.play(&sounds.cancel);
+                } else {
+                    sounds.play(&sounds.confirm);
+                }
+                battle_log.open = !battle_log.open;
+                battle_log.scroll = 0;
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Move(direction), false, _) if battle_log.open => {
+                let scrolled = match direction {
+                    Direction::Up | Direction::Left => battle_log.scroll_back(),
+                    Direction::Down | Direction::Right => battle_log.scroll_forward(),
+                };
+                if scrolled {
+                    sounds.play(&sounds.blip);
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, input, false, _) if battle_log.open && input != InputState::Rest => {
+                sounds.play(&sounds.cancel);
+                battle_log.open = false;
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, input, false, true) if !battle_state.notification_revealed => {
+                if input != InputState::Rest {
+                    battle_state.reveal_notification();
+                    *input_state = InputState::Rest;
+                }
+            },
             (PlayState::InBattle, input, false, true) => {
                 if input != InputState::Rest && !battle_state.animating {
                     sounds.play(&sounds.confirm);
@@ -43,6 +83,82 @@ impl<'a> System<'a> for HandleBattleMenu {
                     *input_state = InputState::Rest;
                 }
             },
+            (PlayState::InBattle, InputState::Toggle, false, _) if battle_state.item_menu.is_none() => {
+                if battle_state.roster.is_some() {
+                    sounds.play(&sounds.cancel);
+                    battle_state.roster = None;
+                } else {
+                    sounds.play(&sounds.confirm);
+                    battle_state.roster = Some(0);
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Move(direction), false, _) if battle_state.roster.is_some() => {
+                if let Some(index) = battle_state.roster {
+                    let next_index = match direction {
+                        Direction::Up | Direction::Left => {
+                            if index > 0 { index - 1 } else { index }
+                        },
+                        Direction::Down | Direction::Right => {
+                            if index + 1 < enemy_count { index + 1 } else { index }
+                        },
+                    };
+                    if next_index != index {
+                        sounds.play(&sounds.blip);
+                    }
+                    battle_state.roster = Some(next_index);
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Select, false, _) | (PlayState::InBattle, InputState::Escape, false, _)
+                if battle_state.roster.is_some() => {
+                sounds.play(&sounds.cancel);
+                battle_state.roster = None;
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Items, false, _) => {
+                battle_state.roster = None;
+                if battle_state.item_menu.is_some() {
+                    sounds.play(&sounds.cancel);
+                    battle_state.item_menu = None;
+                } else {
+                    sounds.play(&sounds.confirm);
+                    battle_state.item_menu = Some(0);
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Move(direction), false, _) if battle_state.item_menu.is_some() => {
+                if let Some(index) = battle_state.item_menu {
+                    let next_index = match direction {
+                        Direction::Up | Direction::Left => {
+                            if index > 0 { index - 1 } else { index }
+                        },
+                        Direction::Down | Direction::Right => {
+                            if index + 1 < items.len() { index + 1 } else { index }
+                        },
+                    };
+                    if next_index != index {
+                        sounds.play(&sounds.blip);
+                    }
+                    battle_state.item_menu = Some(next_index);
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Select, false, _) if battle_state.item_menu.is_some() => {
+                if let Some(index) = battle_state.item_menu {
+                    if let Some((item, _count)) = items.get(index) {
+                        sounds.play(&sounds.confirm);
+                        battle_state.want_item(item.clone());
+                    }
+                }
+                battle_state.item_menu = None;
+                *input_state = InputState::Rest;
+            },
+            (PlayState::InBattle, InputState::Escape, false, _) if battle_state.item_menu.is_some() => {
+                sounds.play(&sounds.cancel);
+                battle_state.item_menu = None;
+                *input_state = InputState::Rest;
+            },
             (PlayState::InBattle, InputState::Move(direction), false, _) => {
                 if let Some(index) = battle_state.combat_move {
                     let next_index = (match direction {
@@ -0,0 +1,88 @@
+use specs::*;
+use state::*;
+
+fn viewed_spirit<'b>(player: &'b mut Player, inventory_state: &InventoryState, index: usize) -> Option<&'b mut Spirit> {
+    match inventory_state.viewing_storage {
+        true => player.storage.get_mut(index),
+        false => player.spirits.get_mut(index),
+    }
+}
+
+pub struct HandleDetails;
+impl<'a> System<'a> for HandleDetails {
+    type SystemData = (
+        WriteExpect<'a, PlayState>,
+        WriteExpect<'a, InputState>,
+        WriteExpect<'a, InventoryState>,
+        WriteStorage<'a, Player>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (mut play_state, mut input_state, mut inventory_state, mut players, sounds): Self::SystemData) {
+        match (play_state.clone(), input_state.clone()) {
+            (PlayState::Combining, InputState::Inspect) => {
+                let mut found = false;
+                for player in (&mut players).join() {
+                    found = viewed_spirit(player, &inventory_state, inventory_state.index).is_some();
+                }
+                if found {
+                    sounds.play(&sounds.confirm);
+                    *play_state = PlayState::Details(inventory_state.index);
+                } else {
+                    sounds.play(&sounds.cancel);
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Details(index), InputState::Select) => {
+                let mut name_entry = String::new();
+                for player in (&mut players).join() {
+                    if let Some(spirit) = viewed_spirit(player, &inventory_state, index) {
+                        name_entry = spirit.nickname.clone().unwrap_or_default();
+                    }
+                }
+                sounds.play(&sounds.confirm);
+                inventory_state.name_entry = name_entry;
+                *play_state = PlayState::Naming(index);
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Details(_), InputState::Escape) | (PlayState::Details(_), InputState::Inspect) => {
+                sounds.play(&sounds.cancel);
+                *play_state = PlayState::Combining;
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Naming(_), InputState::Typed(text)) => {
+                for character in text.chars() {
+                    if !character.is_control() && inventory_state.name_entry.chars().count() < MAX_NICKNAME_LENGTH {
+                        inventory_state.name_entry.push(character);
+                    }
+                }
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Naming(_), InputState::Erase) => {
+                inventory_state.name_entry.pop();
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Naming(index), InputState::Select) => {
+                let nickname = inventory_state.name_entry.trim().to_string();
+                for player in (&mut players).join() {
+                    if let Some(spirit) = viewed_spirit(player, &inventory_state, index) {
+                        spirit.nickname = match nickname.len() {
+                            0 => None,
+                            _ => Some(nickname.clone()),
+                        };
+                    }
+                }
+                sounds.play(&sounds.confirm);
+                *play_state = PlayState::Details(index);
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Naming(index), InputState::Escape) => {
+                sounds.play(&sounds.cancel);
+                *play_state = PlayState::Details(index);
+                *input_state = InputState::Rest;
+            },
+            _ => {}
+        }
+    }
+}
@@ -2,10 +2,11 @@ use ggez::*;
 use specs::*;
 use state::*;
 use input::Direction;
+use std::cmp;
 
 pub struct HandleInventory;
 
-fn move_cursor<'a>(inventory_state: &mut WriteExpect<'a, InventoryState>, direction: Direction) -> bool {
+fn move_cursor<'a>(inventory_state: &mut WriteExpect<'a, InventoryState>, direction: Direction, capacity: usize) -> bool {
     match direction {
         Direction::Up => {
             if inventory_state.index < 5 {
@@ -24,7 +25,7 @@ fn move_cursor<'a>(inventory_state: &mut WriteExpect<'a, InventoryState>, direct
             }
         },
         Direction::Right => {
-            if inventory_state.index >= 24 {
+            if inventory_state.index + 1 >= capacity {
                 false
             } else {
                 inventory_state.index += 1;
@@ -32,7 +33,7 @@ fn move_cursor<'a>(inventory_state: &mut WriteExpect<'a, InventoryState>, direct
             }
         },
         Direction::Down => {
-            if inventory_state.index >= 20 {
+            if inventory_state.index + 5 >= capacity {
                 false
             } else {
                 inventory_state.index += 5;
@@ -41,69 +42,8 @@ fn move_cursor<'a>(inventory_state: &mut WriteExpect<'a, InventoryState>, direct
         },
     }
 }
-fn next_spirit(spirit: Spirit) -> Spirit {
-    match spirit.element {
-        SpiritType::Fire(level) => {
-            Spirit {
-                element: SpiritType::Fire(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Water(level) => {
-            Spirit {
-                element: SpiritType::Water(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Slime(level) => {
-            Spirit {
-                element: SpiritType::Slime(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Light(level) => {
-            Spirit {
-                element: SpiritType::Light(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Dark(level) => {
-            Spirit {
-                element: SpiritType::Dark(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-    }
+fn page_capacity(count: usize) -> usize {
+    cmp::max(1, (count + INVENTORY_PAGE_SIZE - 1) / INVENTORY_PAGE_SIZE) * INVENTORY_PAGE_SIZE
 }
 fn select_fighter<'a>(
     inventory_state: &InventoryState,
@@ -116,7 +56,7 @@ fn select_fighter<'a>(
     let mut found = false;
     for (entity, spirit, player_spirit) in (&**entities, spirits, player_spirits).join() {
         if idx == inventory_state.index && spirit.health > 0 {
-            battle_state.active_entity = Some(entity);
+            battle_state.send_out(entity);
             battle_state.retreating = false;
             battle_state.enemy_attacking = None;
             battle_state.notification = None;
@@ -129,28 +69,48 @@ fn select_fighter<'a>(
     }
     found
 }
-fn combine_spirits(player: &mut Player, index: usize) -> bool {
-    let mut compatriots = Vec::new();
-    let mut collided = false;
-    if let Some(spirit) = player.spirits.get(index).map(|s| s.clone()) {
-        for (idx, other) in player.spirits.iter().enumerate() {
-            if other.element == spirit.element {
-                compatriots.push(idx);
-            }
-        }
-        let used = required_spirits(&spirit.element) as usize;
-        if compatriots.len() >= used {
-            compatriots.reverse();
-            for (count, idx) in compatriots.iter().enumerate() {
-                if count < used {
-                    player.spirits.remove(*idx);
-                }
-            }
-            player.spirits.insert(0, next_spirit(spirit));
-            collided = true;
-        }
+fn combine_spirits(
+    player: &mut Player,
+    bag: &mut Bag,
+    index: usize,
+    inheritance: &Inheritance,
+    difficulty: &Difficulty,
+) -> Option<SpiritType> {
+    let plan = match plan_fusion(&player.spirits, bag.count(&ItemType::Catalyst), index, inheritance, difficulty) {
+        Some(plan) => plan,
+        None => return None,
+    };
+    let element = plan.result.element.clone();
+    if plan.catalyst {
+        bag.take(&ItemType::Catalyst);
+    }
+    let mut consumed = plan.consumed.clone();
+    consumed.sort();
+    let position = index - consumed.iter().filter(|idx| **idx < index).count();
+    for idx in consumed.iter().rev() {
+        player.spirits.remove(*idx);
+    }
+    player.spirits.insert(position, plan.result);
+    Some(element)
+}
+fn current_spirits<'b>(player: &'b mut Player, inventory_state: &InventoryState) -> &'b mut Vec<Spirit> {
+    match inventory_state.viewing_storage {
+        true => &mut player.storage,
+        false => &mut player.spirits,
     }
-    collided
+}
+fn release_spirit(player: &mut Player, inventory_state: &InventoryState) -> bool {
+    let index = inventory_state.index;
+    let party_count = player.spirits.len();
+    let spirits = current_spirits(player, inventory_state);
+    let releasable = match spirits.get(index) {
+        Some(spirit) => !spirit.locked && (inventory_state.viewing_storage || party_count > 1),
+        None => false,
+    };
+    if releasable {
+        spirits.remove(index);
+    }
+    releasable
 }
 impl<'a> System<'a> for HandleInventory {
     type SystemData = (
@@ -159,31 +119,148 @@ impl<'a> System<'a> for HandleInventory {
         WriteExpect<'a, InputState>,
         WriteExpect<'a, BattleState>,
         WriteExpect<'a, InventoryState>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Difficulty>,
+        ReadStorage<'a, WorldEntity>,
+        ReadStorage<'a, Stair>,
+        ReadStorage<'a, Shrine>,
         WriteStorage<'a, Player>,
+        WriteStorage<'a, Bag>,
         WriteStorage<'a, Spirit>,
         WriteStorage<'a, PlayerSpirit>,
+        WriteExpect<'a, Bestiary>,
+        WriteExpect<'a, RunStats>,
         ReadExpect<'a, Sounds>,
     );
-    fn run(&mut self, (entities, mut play_state, mut input_state, mut battle_state, mut inventory_state, mut players, mut spirits, mut player_spirits, sounds): Self::SystemData) {
+    fn run(&mut self, (entities, mut play_state, mut input_state, mut battle_state, mut inventory_state, settings, difficulty, world_entities, stairs, shrines, mut players, mut bags, mut spirits, mut player_spirits, mut bestiary, mut run_stats, sounds): Self::SystemData) {
         match (play_state.clone(), battle_state.retreating) {
             (PlayState::Combining, _) => {
+                match input_state.clone() {
+                    InputState::Release | InputState::Rest => {},
+                    _ => inventory_state.releasing = None,
+                }
+                let mut box_available = false;
+                let mut storage_count = 0;
+                for (player_loc, player) in (&world_entities, &players).join() {
+                    storage_count = player.storage.len();
+                    for (world_entity, _stair) in (&world_entities, &stairs).join() {
+                        if distance(world_entity.location, player_loc.location) <= 1 {
+                            box_available = true;
+                        }
+                    }
+                    for (world_entity, _shrine) in (&world_entities, &shrines).join() {
+                        if distance(world_entity.location, player_loc.location) <= 1 {
+                            box_available = true;
+                        }
+                    }
+                }
                 match input_state.clone() {
                     InputState::Move(direction) => {
-                        if move_cursor(&mut inventory_state, direction) {
+                        let capacity = match inventory_state.viewing_storage {
+                            true => page_capacity(storage_count),
+                            false => page_capacity(settings.party_size),
+                        };
+                        if move_cursor(&mut inventory_state, direction, capacity) {
                             sounds.play(&sounds.blip);
                         }
                         *input_state = InputState::Rest;
                     },
+                    InputState::Toggle => {
+                        if box_available || inventory_state.viewing_storage {
+                            sounds.play(&sounds.confirm);
+                            inventory_state.viewing_storage = !inventory_state.viewing_storage;
+                            inventory_state.index = 0;
+                        } else {
+                            sounds.play(&sounds.cancel);
+                        }
+                        *input_state = InputState::Rest;
+                    },
+                    InputState::Transfer => {
+                        let mut moved = false;
+                        if box_available {
+                            for player in (&mut players).join() {
+                                moved = match inventory_state.viewing_storage {
+                                    true => player.withdraw(inventory_state.index, settings.party_size),
+                                    false => player.deposit(inventory_state.index),
+                                };
+                            }
+                        }
+                        if moved {
+                            sounds.play(&sounds.confirm);
+                        } else {
+                            sounds.play(&sounds.cancel);
+                        }
+                        *input_state = InputState::Rest;
+                    },
                     InputState::Select => {
-                        for player in (&mut players).join() {
-                            if combine_spirits(player, inventory_state.index) {
-                                sounds.play(&sounds.collide);
+                        if inventory_state.viewing_storage {
+                            let mut moved = false;
+                            for player in (&mut players).join() {
+                                moved = player.withdraw(inventory_state.index, settings.party_size);
+                            }
+                            if moved {
+                                sounds.play(&sounds.confirm);
+                            } else {
+                                sounds.play(&sounds.cancel);
+                            }
+                        } else {
+                            let mut can_fuse = false;
+                            for (player, bag) in (&players, &bags).join() {
+                                can_fuse = plan_fusion(
+                                    &player.spirits,
+                                    bag.count(&ItemType::Catalyst),
+                                    inventory_state.index,
+                                    &settings.inheritance,
+                                    &difficulty,
+                                ).is_some();
+                            }
+                            if can_fuse {
+                                sounds.play(&sounds.confirm);
+                                *play_state = PlayState::Fusing(inventory_state.index);
+                            } else {
+                                sounds.play(&sounds.cancel);
                             }
                         }
                         *input_state = InputState::Rest;
                     }
+                    InputState::Release => {
+                        if inventory_state.releasing == Some(inventory_state.index) {
+                            let mut released = false;
+                            for player in (&mut players).join() {
+                                released = release_spirit(player, &inventory_state);
+                            }
+                            if released {
+                                sounds.play(&sounds.collide);
+                            } else {
+                                sounds.play(&sounds.cancel);
+                            }
+                            inventory_state.releasing = None;
+                        } else {
+                            sounds.play(&sounds.blip);
+                            inventory_state.releasing = Some(inventory_state.index);
+                        }
+                        *input_state = InputState::Rest;
+                    },
+                    InputState::Lock => {
+                        for player in (&mut players).join() {
+                            if let Some(spirit) = current_spirits(player, &inventory_state).get_mut(inventory_state.index) {
+                                spirit.locked = !spirit.locked;
+                                sounds.play(&sounds.confirm);
+                            }
+                        }
+                        *input_state = InputState::Rest;
+                    },
+                    InputState::Sort => {
+                        inventory_state.sort_mode = inventory_state.sort_mode.next();
+                        for player in (&mut players).join() {
+                            inventory_state.sort_mode.sort(current_spirits(player, &inventory_state));
+                        }
+                        sounds.play(&sounds.blip);
+                        *input_state = InputState::Rest;
+                    },
                     InputState::Escape => {
                         sounds.play(&sounds.cancel);
+                        inventory_state.viewing_storage = false;
                         *play_state = PlayState::InWorld;
                         *input_state = InputState::Rest;
                     },
@@ -192,10 +269,33 @@ impl<'a> System<'a> for HandleInventory {
                     }
                 }
             },
+            (PlayState::Fusing(index), _) => {
+                match input_state.clone() {
+                    InputState::Select => {
+                        for (player, bag) in (&mut players, &mut bags).join() {
+                            if let Some(element) = combine_spirits(player, bag, index, &settings.inheritance, &difficulty) {
+                                bestiary.own(&element);
+                                run_stats.fusions += 1;
+                                sounds.play(&sounds.collide);
+                            }
+                        }
+                        *play_state = PlayState::Combining;
+                        *input_state = InputState::Rest;
+                    },
+                    InputState::Escape => {
+                        sounds.play(&sounds.cancel);
+                        *play_state = PlayState::Combining;
+                        *input_state = InputState::Rest;
+                    },
+                    _ => {
+
+                    }
+                }
+            },
             (PlayState::InBattle, true) => {
                 match input_state.clone() {
                     InputState::Move(direction) => {
-                        if move_cursor(&mut inventory_state, direction) {
+                        if move_cursor(&mut inventory_state, direction, page_capacity(settings.party_size)) {
                             sounds.play(&sounds.blip);
                         }
                         *input_state = InputState::Rest;
@@ -0,0 +1,105 @@
+use specs::*;
+use state::*;
+use input::Direction;
+
+pub struct HandleItemMenu;
+impl<'a> System<'a> for HandleItemMenu {
+    type SystemData = (
+        WriteExpect<'a, PlayState>,
+        WriteExpect<'a, InputState>,
+        WriteExpect<'a, InventoryState>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Bag>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (mut play_state, mut input_state, mut inventory_state, mut players, mut bags, settings, sounds): Self::SystemData) {
+        match (play_state.clone(), input_state.clone()) {
+            (PlayState::InWorld, InputState::Items) | (PlayState::Combining, InputState::Items) => {
+                sounds.play(&sounds.confirm);
+                inventory_state.viewing_storage = false;
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Items { index: 0, opened_from: Box::new(play_state.clone()) };
+            },
+            (PlayState::Items { index, opened_from }, InputState::Move(direction)) => {
+                for (player, bag) in (&players, &bags).join() {
+                    let moved = match direction {
+                        Direction::Up => {
+                            if index > 0 {
+                                *play_state = PlayState::Items { index: index - 1, opened_from: opened_from.clone() };
+                                true
+                            } else {
+                                false
+                            }
+                        },
+                        Direction::Down => {
+                            if index + 1 < bag.items.len() {
+                                *play_state = PlayState::Items { index: index + 1, opened_from: opened_from.clone() };
+                                true
+                            } else {
+                                false
+                            }
+                        },
+                        Direction::Left => {
+                            if inventory_state.index > 0 {
+                                inventory_state.index -= 1;
+                                true
+                            } else {
+                                false
+                            }
+                        },
+                        Direction::Right => {
+                            if inventory_state.index + 1 < player.spirits.len() {
+                                inventory_state.index += 1;
+                                true
+                            } else {
+                                false
+                            }
+                        },
+                    };
+                    if moved {
+                        sounds.play(&sounds.blip);
+                    }
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Items { index, opened_from }, InputState::Select) => {
+                for (player, bag) in (&mut players, &mut bags).join() {
+                    let item = bag.items.get(index).map(|(item, _count)| item.clone());
+                    let used = if item == Some(ItemType::Revive) {
+                        player.fainted.len() > 0
+                            && player.spirits.len() < settings.party_size
+                            && bag.take(&ItemType::Revive)
+                            && player.revive_one(settings.party_size)
+                    } else {
+                        match (item, player.spirits.get_mut(inventory_state.index)) {
+                            (Some(item), Some(spirit)) => {
+                                if item.use_on_spirit(spirit) {
+                                    bag.take(&item)
+                                } else {
+                                    false
+                                }
+                            },
+                            _ => false,
+                        }
+                    };
+                    if used {
+                        sounds.play(&sounds.confirm);
+                        if index >= bag.items.len() && index > 0 {
+                            *play_state = PlayState::Items { index: index - 1, opened_from: opened_from.clone() };
+                        }
+                    } else {
+                        sounds.play(&sounds.cancel);
+                    }
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Items { opened_from, .. }, InputState::Escape) | (PlayState::Items { opened_from, .. }, InputState::Items) => {
+                sounds.play(&sounds.cancel);
+                *input_state = InputState::Rest;
+                *play_state = *opened_from;
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use specs::*;
+use state::*;
+
+pub struct HandleLeaderboard;
+impl<'a> System<'a> for HandleLeaderboard {
+    type SystemData = (
+        WriteExpect<'a, PlayState>,
+        WriteExpect<'a, InputState>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (mut play_state, mut input_state, sounds): Self::SystemData) {
+        match (play_state.clone(), input_state.clone()) {
+            (PlayState::MainMenu(2), InputState::Select) => {
+                sounds.play(&sounds.confirm);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Leaderboard(false);
+            },
+            (PlayState::Leaderboard(daily), InputState::Move(_)) | (PlayState::Leaderboard(daily), InputState::Toggle) => {
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Leaderboard(!daily);
+            },
+            (PlayState::Leaderboard(_), InputState::Escape) | (PlayState::Leaderboard(_), InputState::Select) => {
+                sounds.play(&sounds.cancel);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::MainMenu(2);
+            },
+            _ => {}
+        }
+    }
+}
@@ -1,15 +1,37 @@
 use ggez::*;
 use specs::*;
 use state::*;
+use input::Direction;
+use rand::*;
+
+fn next_choice(choice: &CaptureChoice, charms_left: bool) -> CaptureChoice {
+    match choice {
+        CaptureChoice::Leave => CaptureChoice::Attempt,
+        CaptureChoice::Attempt => {
+            if charms_left {
+                CaptureChoice::Charm
+            } else {
+                CaptureChoice::Leave
+            }
+        },
+        CaptureChoice::Charm => CaptureChoice::Leave,
+    }
+}
 
 pub struct HandleLootMenu;
 impl<'a> System<'a> for HandleLootMenu {
     type SystemData = (
         WriteExpect<'a, PlayState>,
         WriteExpect<'a, InputState>,
+        WriteStorage<'a, Player>,
+        WriteStorage<'a, Bag>,
+        ReadExpect<'a, Level>,
+        WriteExpect<'a, Bestiary>,
+        WriteExpect<'a, RunStats>,
+        ReadExpect<'a, Settings>,
         ReadExpect<'a, Sounds>,
     );
-    fn run(&mut self, (mut play_state, mut input_state, sounds): Self::SystemData) {
+    fn run(&mut self, (mut play_state, mut input_state, mut players, mut bags, level, mut bestiary, mut run_stats, settings, sounds): Self::SystemData) {
         match (play_state.clone(), input_state.clone()) {
             (PlayState::Looting { captured, lost }, InputState::Select) => {
                 sounds.play(&sounds.confirm);
@@ -21,6 +43,76 @@ impl<'a> System<'a> for HandleLootMenu {
                 *input_state = InputState::Rest;
                 *play_state = PlayState::InWorld;
             },
+            (PlayState::Capturing { candidates, choices, index }, InputState::Move(direction)) => {
+                let next_index = match direction {
+                    Direction::Up | Direction::Left => {
+                        if index > 0 { index - 1 } else { index }
+                    },
+                    Direction::Down | Direction::Right => {
+                        if index + 1 < candidates.len() { index + 1 } else { index }
+                    },
+                };
+                if next_index != index {
+                    sounds.play(&sounds.blip);
+                }
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Capturing {
+                    candidates,
+                    choices,
+                    index: next_index,
+                };
+            },
+            (PlayState::Capturing { candidates, mut choices, index }, InputState::Select) => {
+                let mut charms = 0;
+                for bag in (&bags).join() {
+                    charms = bag.count(&ItemType::CaptureCharm);
+                }
+                let charms_used = choices.iter()
+                    .filter(|choice| **choice == CaptureChoice::Charm)
+                    .count() as u32;
+                if let Some(choice) = choices.get_mut(index) {
+                    *choice = next_choice(choice, charms_used < charms);
+                }
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Capturing {
+                    candidates,
+                    choices,
+                    index,
+                };
+            },
+            (PlayState::Capturing { candidates, choices, index }, InputState::Escape) => {
+                let mut rng = thread_rng();
+                let mut captured = Vec::new();
+                let mut lost = Vec::new();
+                for (player, bag) in (&mut players, &mut bags).join() {
+                    for (element, choice) in candidates.iter().zip(choices.iter()) {
+                        let caught = match choice {
+                            CaptureChoice::Leave => continue,
+                            CaptureChoice::Attempt => rng.gen_bool(defeated_capture_chance(element)),
+                            CaptureChoice::Charm => {
+                                bag.take(&ItemType::CaptureCharm)
+                                    || rng.gen_bool(defeated_capture_chance(element))
+                            },
+                        };
+                        if caught {
+                            captured.push(element.clone());
+                            player.add_spirit(Spirit::captured(element.clone(), level.depth), settings.party_size);
+                            bestiary.own(element);
+                        } else {
+                            lost.push(element.clone());
+                        }
+                    }
+                }
+                run_stats.spirits_captured += captured.len() as u32;
+                run_stats.spirits_lost += lost.len() as u32;
+                sounds.play(&sounds.collide);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Looting {
+                    captured,
+                    lost,
+                };
+            },
             _ => {}
         }
     }
@@ -2,6 +2,11 @@ mod world;
 mod combat;
 mod inventory;
 mod looting;
+mod items;
+mod settings;
+mod details;
+mod bestiary;
+mod leaderboard;
 
 use state::*;
 use ggez::*;
@@ -10,6 +15,11 @@ pub use self::world::HandleMove;
 pub use self::combat::HandleBattleMenu;
 pub use self::inventory::HandleInventory;
 pub use self::looting::HandleLootMenu;
+pub use self::items::HandleItemMenu;
+pub use self::settings::HandleSettings;
+pub use self::details::HandleDetails;
+pub use self::bestiary::HandleBestiary;
+pub use self::leaderboard::HandleLeaderboard;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Direction {
@@ -19,15 +29,23 @@ pub enum Direction {
     Right,
 }
 
+pub const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Right,
+    Direction::Down,
+    Direction::Left,
+];
+
 
 pub struct HandleMainMenu;
 impl<'a> System<'a> for HandleMainMenu {
     type SystemData = (
         WriteExpect<'a, PlayState>,
         WriteExpect<'a, InputState>,
+        WriteExpect<'a, Difficulty>,
         ReadExpect<'a, Sounds>,
     );
-    fn run(&mut self, (mut play_state, mut input_state, sounds): Self::SystemData) {
+    fn run(&mut self, (mut play_state, mut input_state, mut difficulty, sounds): Self::SystemData) {
         match (play_state.clone(), input_state.clone()) {
             (PlayState::MainMenu(val), InputState::Move(dir)) => {
                 sounds.play(&sounds.blip);
@@ -50,8 +68,34 @@ impl<'a> System<'a> for HandleMainMenu {
             (PlayState::MainMenu(0), InputState::Select) => {
                 sounds.play(&sounds.confirm);
                 *input_state = InputState::Rest;
+                *play_state = PlayState::NewGame(
+                    DIFFICULTIES.iter().position(|option| *option == *difficulty).unwrap_or(1)
+                );
+            },
+            (PlayState::NewGame(index), InputState::Move(dir)) => {
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::NewGame(match dir {
+                    Direction::Up | Direction::Left => (index + DIFFICULTIES.len() - 1) % DIFFICULTIES.len(),
+                    Direction::Down | Direction::Right => (index + 1) % DIFFICULTIES.len(),
+                });
+            },
+            (PlayState::NewGame(index), InputState::Select) => {
+                sounds.play(&sounds.confirm);
+                *input_state = InputState::Rest;
+                *difficulty = DIFFICULTIES[index];
                 *play_state = PlayState::Stairs(0);
             },
+            (PlayState::NewGame(_), InputState::Escape) => {
+                sounds.play(&sounds.cancel);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::MainMenu(0);
+            },
+            (PlayState::MainMenu(1), InputState::Select) => {
+                sounds.play(&sounds.confirm);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::Settings(0);
+            },
             (PlayState::GameOver, _) => {
                 if *input_state != InputState::Rest {
                     sounds.play(&sounds.confirm);
@@ -0,0 +1,47 @@
+use specs::*;
+use state::*;
+use input::Direction;
+
+pub struct HandleSettings;
+impl<'a> System<'a> for HandleSettings {
+    type SystemData = (
+        WriteExpect<'a, PlayState>,
+        WriteExpect<'a, InputState>,
+        WriteExpect<'a, Settings>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (mut play_state, mut input_state, mut settings, sounds): Self::SystemData) {
+        match (play_state.clone(), input_state.clone()) {
+            (PlayState::Settings(index), InputState::Move(direction)) => {
+                let option_count = settings.options().len();
+                match direction {
+                    Direction::Up => {
+                        *play_state = PlayState::Settings((index + option_count - 1) % option_count);
+                    },
+                    Direction::Down => {
+                        *play_state = PlayState::Settings((index + 1) % option_count);
+                    },
+                    Direction::Left => {
+                        settings.change(index, false);
+                    },
+                    Direction::Right => {
+                        settings.change(index, true);
+                    },
+                }
+                sounds.play(&sounds.blip);
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Settings(index), InputState::Select) => {
+                settings.change(index, true);
+                sounds.play(&sounds.confirm);
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Settings(_), InputState::Escape) => {
+                sounds.play(&sounds.cancel);
+                *input_state = InputState::Rest;
+                *play_state = PlayState::MainMenu(1);
+            },
+            _ => {}
+        }
+    }
+}
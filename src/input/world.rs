@@ -9,16 +9,44 @@ impl<'a> System<'a> for HandleMove {
         WriteExpect<'a, PlayState>,
         WriteExpect<'a, InputState>,
         ReadExpect<'a, Level>,
+        Entities<'a>,
         WriteStorage<'a, WorldEntity>,
         ReadStorage<'a, Player>,
+        ReadStorage<'a, Encounter>,
+        ReadStorage<'a, Door>,
+        WriteStorage<'a, Bag>,
         ReadExpect<'a, Sounds>,
     );
-    fn run(&mut self, (mut play_state, mut input_state, level, mut world_entities, players, sounds): Self::SystemData) {
+    fn run(&mut self, (mut play_state, mut input_state, level, entities, mut world_entities, players, encounters, doors, mut bags, sounds): Self::SystemData) {
         match (play_state.clone(), input_state.clone()) {
             (PlayState::InWorld, InputState::Move(direction)) => {
-                for (mut world_entity, player) in (&mut world_entities, &players).join() {
+                // Hold on to the move until the player has finished walking to their last tile.
+                if (&world_entities, &players).join().any(|(world_entity, _player)| world_entity.moving()) {
+                    return;
+                }
+                let mut locked_doors = Vec::new();
+                for (entity, world_entity, _door) in (&*entities, &world_entities, &doors).join() {
+                    locked_doors.push((entity, world_entity.location));
+                }
+                for (mut world_entity, player, bag) in (&mut world_entities, &players, &mut bags).join() {
                     match move_in_level(world_entity.location, &direction, &level) {
-                        Some(next) => { world_entity.location = next; },
+                        Some(next) => {
+                            let mut blocked = false;
+                            for (door, location) in locked_doors.iter() {
+                                if *location == next {
+                                    if bag.take(&ItemType::Key) {
+                                        sounds.play(&sounds.confirm);
+                                        (*entities).delete(*door);
+                                    } else {
+                                        sounds.play(&sounds.cancel);
+                                        blocked = true;
+                                    }
+                                }
+                            }
+                            if !blocked {
+                                world_entity.move_to(next);
+                            }
+                        },
                         _ => {}
                     }
                 }
@@ -28,7 +56,38 @@ impl<'a> System<'a> for HandleMove {
                 sounds.play(&sounds.cancel);
                 *input_state = InputState::Rest;
                 *play_state = PlayState::Combining;
-            }
+            },
+            (PlayState::InWorld, InputState::Inspect) => {
+                let mut player_loc = None;
+                for (world_entity, _player) in (&world_entities, &players).join() {
+                    player_loc = Some(world_entity.location);
+                }
+                let mut inspected = None;
+                if let Some(player_loc) = player_loc {
+                    for (entity, world_entity, _encounter) in (&*entities, &world_entities, &encounters).join() {
+                        if distance(world_entity.location, player_loc) <= 1 {
+                            inspected = Some(entity);
+                        }
+                    }
+                }
+                match inspected {
+                    Some(entity) => {
+                        sounds.play(&sounds.confirm);
+                        *play_state = PlayState::Inspecting(entity);
+                    },
+                    None => {
+                        sounds.play(&sounds.cancel);
+                    },
+                }
+                *input_state = InputState::Rest;
+            },
+            (PlayState::Inspecting(_), input) => {
+                if input != InputState::Rest {
+                    sounds.play(&sounds.cancel);
+                    *input_state = InputState::Rest;
+                    *play_state = PlayState::InWorld;
+                }
+            },
             _ => {}
         }
     }
@@ -3,9 +3,14 @@ extern crate ggez;
 #[macro_use]
 extern crate specs_derive;
 extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate ron;
 
 mod render;
 mod state;
+mod tiers;
 mod input;
 mod systems;
 use specs::*;
@@ -14,7 +19,7 @@ use ggez::audio::*;
 use ggez::graphics::*;
 use ggez::graphics::spritebatch::*;
 use ggez::event::*;
-use render::render_world;
+use render::{render_world, render_letterbox, load_font, Atlas, ATLAS_FILE, DrawList, GgezBackend, RenderBackend, Viewport};
 use state::*;
 use input::*;
 use systems::*;
@@ -22,6 +27,22 @@ use std::path;
 use std::path::Path;
 use std::collections::*;
 use std::time::Duration;
+use std::io::Write;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+fn load_ron<T: DeserializeOwned>(ctx: &mut Context, path: &str) -> Option<T> {
+    ctx.filesystem.open(path).ok()
+        .and_then(|file| ron::de::from_reader(file).ok())
+}
+
+fn save_ron<T: Serialize>(ctx: &mut Context, path: &str, value: &T) -> GameResult<()> {
+    let serialized = ron::ser::to_string(value)
+        .map_err(|err| GameError::UnknownError(err.to_string()))?;
+    let mut file = ctx.filesystem.create(path)?;
+    file.write_all(serialized.as_bytes())?;
+    Ok(())
+}
 
 pub struct CameraSystem;
 impl<'a> System<'a> for CameraSystem {
@@ -29,22 +50,14 @@ impl<'a> System<'a> for CameraSystem {
         Write<'a, Camera>,
         ReadStorage<'a, WorldEntity>,
         ReadStorage<'a, Player>,
+        ReadExpect<'a, Level>,
+        Read<'a, Duration>,
     );
 
-    fn run(&mut self, (mut camera, entities, players): Self::SystemData) {
-        for (entity, player) in (&entities, &players).join() {
-            if entity.location.0 >= camera.width / 2 {
-                let x_offset = entity.location.0 - camera.width / 2;
-                camera.x_offset = x_offset;
-            } else {
-                camera.x_offset = 0;
-            }
-            if entity.location.1 >= camera.height / 2 {
-                let y_offset = entity.location.1 - camera.height / 2;
-                camera.y_offset = y_offset;
-            } else {
-                camera.y_offset = 0;
-            }
+    fn run(&mut self, (mut camera, entities, players, level, delta_time): Self::SystemData) {
+        for (entity, _player) in (&entities, &players).join() {
+            let target = camera.target(entity.position(), level.bounds());
+            camera.ease_towards(target, *delta_time);
         }
     }
 }
@@ -58,28 +71,52 @@ impl<'a, 'b> GameState<'a, 'b> {
         world.register::<PlayerSpirit>();
         world.register::<Player>();
         world.register::<CombatEffects>();
+        world.register::<Animation>();
         world.register::<Stair>();
+        world.register::<Pickup>();
+        world.register::<Door>();
+        world.register::<Bag>();
+        world.register::<Shrine>();
         world.add_resource(Camera::new(SCREEN_SIZE.0, SCREEN_SIZE.1));
         world.add_resource(BattleState::new());
+        world.add_resource(BattleLog::default());
+        world.add_resource(FloatingNumbers::default());
         world.add_resource(PlayState::MainMenu(0));
         world.add_resource(InputState::Rest);
         world.add_resource(InventoryState::new());
         world.add_resource(Duration::new(0, 0));
-        world.add_resource(Level::new(0));
+        world.add_resource(Level::new(0, 0));
+        world.add_resource(Settings::default());
+        world.add_resource(Bestiary::default());
+        world.add_resource(Difficulty::default());
+        world.add_resource(RunStats::default());
+        world.add_resource(HighScores::default());
+        world.add_resource(Viewport::default());
 
         let dispatcher = DispatcherBuilder::new()
             .with(HandleMove, "move", &[])
             .with(HandleBattleMenu, "battle_menu", &[])
             .with(HandleInventory, "inventory", &[])
             .with(HandleLootMenu, "looting", &[])
+            .with(HandleItemMenu, "item_menu", &[])
+            .with(HandleSettings, "settings", &[])
+            .with(HandleDetails, "details", &[])
+            .with(HandleBestiary, "bestiary", &[])
+            .with(HandleLeaderboard, "leaderboard", &[])
             .with(HandleMainMenu, "main_menu", &[])
-            .with(CameraSystem, "camera", &[])
+            .with(TweenMovement, "tween", &[])
+            .with(CameraSystem, "camera", &["tween"])
             .with(FindEncounters, "find", &[])
+            .with(FindPickups, "pickups", &[])
+            .with(VisitShrines, "shrines", &[])
             .with(WanderEncounters, "wander", &[])
             .with(WatchAttack, "attack", &[])
+            .with(WatchItems, "items", &[])
             .with(WatchSpirits, "spirits", &[])
-            .with(TickEffects, "tick_combat", &["attack"])
+            .with(TickEffects, "tick_combat", &["attack", "items"])
             .with(EnemyCombat, "enemy_attack", &["tick_combat"])
+            .with(AnimateSprites, "animate", &["enemy_attack"])
+            .with(AdvanceNotifications, "advance", &["animate"])
             .build();
 
         GameState {
@@ -109,6 +146,11 @@ impl<'a, 'b> GameState<'a, 'b> {
         let mut encounter = GameState::sound(ctx, &"/encounter.wav")?;
         let mut lose = GameState::sound(ctx, &"/lose.wav")?;
         self.world.add_resource(SpriteBatch::new(image));
+        let atlas: Atlas = load_ron(ctx, ATLAS_FILE)
+            .ok_or_else(|| GameError::ResourceLoadError(format!("Could not read {}", ATLAS_FILE)))?;
+        self.world.add_resource(atlas);
+        let font = load_font(ctx)?;
+        self.world.add_resource(font);
         self.world.add_resource(Sounds {
             fire,
             water,
@@ -123,8 +165,63 @@ impl<'a, 'b> GameState<'a, 'b> {
             lose,
             pending: Vec::new(),
         });
+        let bestiary: Bestiary = load_ron(ctx, BESTIARY_FILE).unwrap_or_default();
+        self.world.add_resource(bestiary);
+        let high_scores: HighScores = load_ron(ctx, HIGH_SCORE_FILE).unwrap_or_default();
+        self.world.add_resource(high_scores);
+        let window = graphics::get_drawable_size(ctx);
+        self.fit_viewport(ctx, window)?;
+        Ok(())
+    }
+    fn fit_viewport(&mut self, ctx: &mut Context, window: (u32, u32)) -> GameResult<()> {
+        let fullscreen = self.world.read_resource::<Settings>().fullscreen;
+        let viewport = Viewport::fit(window, fullscreen);
+        viewport.apply(ctx)?;
+        self.world.add_resource(viewport);
+        Ok(())
+    }
+    fn wants_fullscreen(&self) -> Option<bool> {
+        let fullscreen = self.world.read_resource::<Settings>().fullscreen;
+        if fullscreen != self.world.read_resource::<Viewport>().fullscreen {
+            Some(fullscreen)
+        } else {
+            None
+        }
+    }
+    fn save_bestiary(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let mut bestiary = self.world.write_resource::<Bestiary>();
+        if bestiary.dirty {
+            // Cleared first so a failed save is retried on the next change, not every frame.
+            bestiary.dirty = false;
+            save_ron(ctx, BESTIARY_FILE, &*bestiary)?;
+        }
         Ok(())
     }
+    fn is_naming(&self) -> bool {
+        match *self.world.read_resource::<PlayState>() {
+            PlayState::Naming(_) => true,
+            _ => false,
+        }
+    }
+    fn wants_record(&self) -> bool {
+        *self.world.read_resource::<PlayState>() == PlayState::GameOver
+            && !self.world.read_resource::<RunStats>().recorded
+    }
+    fn record_run(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let mut run_stats = self.world.write_resource::<RunStats>();
+        let mut high_scores = self.world.write_resource::<HighScores>();
+        let players = self.world.read_storage::<Player>();
+        for player in (&players).join() {
+            run_stats.party = player.spirits.iter()
+                .map(|spirit| spirit.element.clone())
+                .collect();
+        }
+        run_stats.score = run_stats.calculate_score();
+        high_scores.runs.push(run_stats.clone());
+        // Marked before saving so a failed save doesn't record the run again every frame.
+        run_stats.recorded = true;
+        save_ron(ctx, HIGH_SCORE_FILE, &*high_scores)
+    }
     fn wants_level(&self) -> Option<u32> {
         if let PlayState::Stairs(depth) = *self.world.read_resource::<PlayState>() {
             Some(depth)
@@ -139,26 +236,50 @@ impl<'a, 'b> EventHandler for GameState<'a, 'b> {
         self.world.add_resource(ggez::timer::get_delta(&ctx));
         self.dispatcher.dispatch(&mut self.world.res);
         if let Some(depth) = self.wants_level() {
-            let level = Level::new(depth);
+            if depth == 0 {
+                let run_stats = RunStats::new(
+                    &self.world.read_resource::<Settings>(),
+                    *self.world.read_resource::<Difficulty>(),
+                );
+                self.world.add_resource(run_stats);
+            }
+            let seed = {
+                let mut run_stats = self.world.write_resource::<RunStats>();
+                run_stats.depth = depth;
+                run_stats.seed
+            };
+            let level = Level::new(depth, seed);
             level.spawn_encounters(&mut self.world);
             self.world.add_resource(level);
             self.world.add_resource(PlayState::InWorld);
         }
+        if let Some(fullscreen) = self.wants_fullscreen() {
+            graphics::set_fullscreen(ctx, fullscreen)?;
+            let window = graphics::get_drawable_size(ctx);
+            self.fit_viewport(ctx, window)?;
+        }
+        if let Err(e) = self.save_bestiary(ctx) {
+            println!("Could not save the bestiary: {}", e);
+        }
+        if self.wants_record() {
+            if let Err(e) = self.record_run(ctx) {
+                println!("Could not save the high scores: {}", e);
+            }
+        }
         self.world.maintain();
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
         graphics::clear(ctx);
-        self.world.write_resource::<SpriteBatch>().clear();
-        render_world(ctx, &mut self.world)?;
-        graphics::set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
-        graphics::draw(
-            ctx,
-            &*self.world.read_resource::<SpriteBatch>(),
-            Point2::new(0.0, 0.0),
-            0.0,
-        )?;
+        let mut list = DrawList::new();
+        render_world(&mut list, &mut self.world)?;
+        {
+            let mut spritebatch = self.world.write_resource::<SpriteBatch>();
+            let font = self.world.read_resource::<Font>();
+            GgezBackend::new(ctx, &mut spritebatch, &font).render(&list)?;
+        }
+        render_letterbox(ctx, &self.world.read_resource::<Viewport>())?;
         graphics::present(ctx);
         ggez::timer::yield_now();
         Ok(())
@@ -171,7 +292,22 @@ impl<'a, 'b> EventHandler for GameState<'a, 'b> {
         _keymod: event::Mod,
         repeat: bool,
     ) {
-        if !repeat {
+        if self.is_naming() {
+            match keycode {
+                Keycode::Return => {
+                    self.world.add_resource(InputState::Select);
+                },
+                Keycode::Backspace => {
+                    self.world.add_resource(InputState::Erase);
+                },
+                Keycode::Escape => {
+                    self.world.add_resource(InputState::Escape);
+                },
+                _ => {
+
+                }
+            }
+        } else if !repeat {
             match keycode {
                 Keycode::W => {
                     self.world.add_resource(InputState::Move(Direction::Up));
@@ -191,17 +327,57 @@ impl<'a, 'b> EventHandler for GameState<'a, 'b> {
                 Keycode::Backspace => {
                     self.world.add_resource(InputState::Escape);
                 },
+                Keycode::E => {
+                    self.world.add_resource(InputState::Inspect);
+                },
+                Keycode::I => {
+                    self.world.add_resource(InputState::Items);
+                },
+                Keycode::Tab => {
+                    self.world.add_resource(InputState::Toggle);
+                },
+                Keycode::Q => {
+                    self.world.add_resource(InputState::Transfer);
+                },
+                Keycode::R => {
+                    self.world.add_resource(InputState::Release);
+                },
+                Keycode::L => {
+                    self.world.add_resource(InputState::Lock);
+                },
+                Keycode::O => {
+                    self.world.add_resource(InputState::Sort);
+                },
                 _ => {
 
                 }
             }
         }
     }
+
+    fn resize_event(&mut self, ctx: &mut Context, _width: u32, _height: u32) {
+        // The drawable size is in real pixels, which may differ from the window size on high DPI screens.
+        let window = graphics::get_drawable_size(ctx);
+        // If the new size can't be applied, keep drawing with the previous viewport.
+        if let Err(e) = self.fit_viewport(ctx, window) {
+            println!("Could not resize the screen: {}", e);
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, text: String) {
+        if self.is_naming() {
+            let typed = match self.world.read_resource::<InputState>().clone() {
+                InputState::Typed(pending) => pending + &text,
+                _ => text,
+            };
+            self.world.add_resource(InputState::Typed(typed));
+        }
+    }
 }
 
 fn main() {
     let ctx = &mut ggez::ContextBuilder::new("Spirits of Semb", "Seurimas")
-        .window_setup(ggez::conf::WindowSetup::default().title("Spirits of Semb"))
+        .window_setup(ggez::conf::WindowSetup::default().title("Spirits of Semb").resizable(true))
         .window_mode(ggez::conf::WindowMode::default().dimensions(SCREEN_SIZE.0, SCREEN_SIZE.1))
         .build().expect("Failed to build ggez context");
 
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+pub const ATLAS_FILE: &str = "/atlas.ron";
+const MISSING_SPRITE: &str = "unknown";
+
+type AtlasRect = (f32, f32, f32, f32);
+
+// Where each named sprite lives in Sprites.png, in pixels. Animations are a list of frames
+// played evenly over the animation's length.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Atlas {
+    pub size: (f32, f32),
+    pub sprites: HashMap<String, AtlasRect>,
+    #[serde(default)]
+    pub animations: HashMap<String, Vec<AtlasRect>>,
+}
+
+impl Atlas {
+    pub fn rect(&self, name: &str) -> AtlasRect {
+        self.sprites.get(name)
+            .or_else(|| self.sprites.get(MISSING_SPRITE))
+            .cloned()
+            .unwrap_or((0.0, 0.0, self.size.0, self.size.1))
+    }
+    // Falls back to the sprite of the same name when there is no animation for it.
+    pub fn frame(&self, name: &str, progress: f32) -> AtlasRect {
+        match self.animations.get(name) {
+            Some(frames) if frames.len() > 0 => {
+                let index = (progress * frames.len() as f32) as usize;
+                frames[index.min(frames.len() - 1)]
+            },
+            _ => self.rect(name),
+        }
+    }
+}
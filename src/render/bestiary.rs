@@ -0,0 +1,102 @@
+use ggez::*;
+use specs::*;
+use ggez::graphics::*;
+use state::*;
+use render::*;
+
+const GRID_LOCATION: (i32, i32) = (16, 16);
+const GRID_CELL: (i32, i32) = (64, 68);
+const GRID_SPRITE: (i32, i32) = (48, 48);
+const ENTRY_AREA: (i32, i32, i32, i32) = (
+    336, 8,
+    SCREEN_SIZE.0 as i32 - 344, SCREEN_SIZE.1 as i32 - 16,
+);
+
+fn status_text(bestiary: &Bestiary, element: &SpiritType) -> &'static str {
+    if bestiary.has_owned(element) {
+        "Owned"
+    } else if bestiary.has_seen(element) {
+        "Seen"
+    } else {
+        "Not yet seen"
+    }
+}
+
+pub fn render_bestiary(list: &mut DrawList, world: &mut World, index: usize) -> GameResult<()> {
+    type SystemData<'a> = (
+        ReadExpect<'a, Bestiary>,
+        ReadExpect<'a, Difficulty>,
+        ReadExpect<'a, Atlas>,
+    );
+    world.exec(|(bestiary, difficulty, atlas): SystemData| -> GameResult<()> {
+        let entries = all_spirit_types();
+        let mut row = 0;
+        let mut column = 0;
+        for (idx, element) in entries.iter().enumerate() {
+            if idx > 0 && entries[idx - 1].element_index() != element.element_index() {
+                row += 1;
+                column = 0;
+            }
+            let x = GRID_LOCATION.0 + column * GRID_CELL.0;
+            let y = GRID_LOCATION.1 + row * GRID_CELL.1;
+            list.sprite(spirit_sprite(&atlas, element,
+                x,
+                y,
+                GRID_SPRITE.0,
+                GRID_SPRITE.1,
+                match bestiary.has_seen(element) {
+                    true => None,
+                    false => Some([0.0, 0.0, 0.0, 1.0].into()),
+                },
+            ));
+            if bestiary.has_owned(element) {
+                list.set_color([1.0, 0.8, 0.0, 1.0].into())?;
+                list.rectangle(DrawMode::Line(2.0), Rect::new_i32(
+                    x - 2, y - 2,
+                    GRID_SPRITE.0 + 4, GRID_SPRITE.1 + 4,
+                ))?;
+            }
+            if idx == index {
+                list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
+                list.rectangle(DrawMode::Line(4.0), Rect::new_i32(
+                    x - 6, y - 6,
+                    GRID_SPRITE.0 + 12, GRID_SPRITE.1 + 12,
+                ))?;
+            }
+            column += 1;
+        }
+        text_outline(list, ENTRY_AREA)?;
+        if let Some(element) = entries.get(index) {
+            let text = match bestiary.has_seen(element) {
+                true => {
+                    let moves: Vec<String> = move_pool(element, true).iter()
+                        .map(|combat_move| combat_move.name.clone())
+                        .collect();
+                    format!("{}\n{}\n{}\n\n{}\n\nMoves: {}",
+                        spirit_name(element),
+                        spirit_level_text(element),
+                        status_text(&bestiary, element),
+                        collide_text(element, &difficulty),
+                        moves.join(", "),
+                    )
+                },
+                false => format!("???\n{}\n{}",
+                    spirit_level_text(element),
+                    status_text(&bestiary, element),
+                ),
+            };
+            text_in_box(list, &format!("{}\n\nSeen {} / {}, owned {} / {}",
+                text,
+                bestiary.seen.len(),
+                entries.len(),
+                bestiary.owned.len(),
+                entries.len(),
+            ), (
+                ENTRY_AREA.0 + 8,
+                ENTRY_AREA.1 + 8,
+                ENTRY_AREA.2 - 16,
+            ))?;
+        }
+        Ok(())
+    })
+}
@@ -2,7 +2,6 @@ use ggez::*;
 use specs::*;
 use specs::world::EntitiesRes;
 use ggez::graphics::*;
-use ggez::graphics::spritebatch::*;
 use state::*;
 use render::*;
 
@@ -43,12 +42,6 @@ const ENEMY_BARS: [(i32, i32); ENEMY_COUNT] = [
     (ENEMY_LOCATIONS[1].0 + 64 + 8, 8),
     ((SCREEN_SIZE.0 / 2) as i32 - BAR_SIZE.0 / 2, 152),
 ];
-const ENEMY_BAR_AREA: (i32, i32, i32, i32) = (
-    0,
-    0,
-    16 + BAR_SIZE.0,
-    48 + BAR_SIZE.1 * 3,
-);
 const ENEMY_LINEUP: (i32, i32, i32, i32) = (
     8, 56 + BAR_SIZE.1 * 3,
     128, 64,
@@ -56,12 +49,27 @@ const ENEMY_LINEUP: (i32, i32, i32, i32) = (
 const ENEMY_LINEUP_SIZE: (i32, i32) = (
     128 / 4, 64 / 2,
 );
+const LINEUP_PAGE: (f32, f32) = (
+    ENEMY_LINEUP.0 as f32, (ENEMY_LINEUP.1 + ENEMY_LINEUP.3) as f32,
+);
+const ROSTER_BAR: (i32, i32) = (
+    ENEMY_LINEUP.0, ENEMY_LINEUP.1 + ENEMY_LINEUP.3 + 24,
+);
+const TARGET_COLOR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+const ROSTER_COLOR: [f32; 4] = [0.0, 1.0, 1.0, 1.0];
 const ENEMY_BAR_INNER_OFFSET: (i32, i32) = (10, 6);
 const ENEMY_DEF_BAR_INNER_OFFSET: (i32, i32) = (10, 18);
 const NOTIFICATION_AREA: (i32, i32, i32, i32) = (
     0, SCREEN_SIZE.1 as i32 - 64,
     SCREEN_SIZE.0 as i32 - 256, 64,
 );
+const LOG_AREA: (i32, i32, i32, i32) = (
+    16, 16,
+    SCREEN_SIZE.0 as i32 - 32, SCREEN_SIZE.1 as i32 - 96,
+);
+const FLOAT_RISE: f32 = 32.0;
+// The last part of a floating number's rise is spent fading out.
+const FLOAT_FADE: f32 = 0.4;
 const MOVE_AREAS: [(f32, f32); 4] = [
     (SCREEN_SIZE.0 as f32 - 248.0, SCREEN_SIZE.1 as f32 - 56.0),
     (SCREEN_SIZE.0 as f32 - 124.0, SCREEN_SIZE.1 as f32 - 56.0),
@@ -73,9 +81,9 @@ const MOVE_REGION: (i32, i32, i32, i32) = (
     256, 64,
 );
 
-fn render_health_bar(ctx: &mut Context, location: (i32, i32), percent: f32) -> GameResult<()> {
-    set_color(ctx, [1.0, 0.0, 0.0, 1.0].into())?;
-    rectangle(ctx, DrawMode::Fill, Rect::new_i32(
+fn render_health_bar(list: &mut DrawList, location: (i32, i32), percent: f32) -> GameResult<()> {
+    list.set_color([1.0, 0.0, 0.0, 1.0].into())?;
+    list.rectangle(DrawMode::Fill, Rect::new_i32(
         location.0,
         location.1,
         (INNER_BAR_SIZE.0 as f32 * percent) as i32,
@@ -84,9 +92,9 @@ fn render_health_bar(ctx: &mut Context, location: (i32, i32), percent: f32) -> G
     Ok(())
 }
 
-fn render_defense_bar(ctx: &mut Context, location: (i32, i32), percent: f32) -> GameResult<()> {
-    set_color(ctx, [0.0, 0.0, 1.0, 1.0].into())?;
-    rectangle(ctx, DrawMode::Fill, Rect::new_i32(
+fn render_defense_bar(list: &mut DrawList, location: (i32, i32), percent: f32) -> GameResult<()> {
+    list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
+    list.rectangle(DrawMode::Fill, Rect::new_i32(
         location.0,
         location.1,
         (INNER_DEF_BAR_SIZE.0 as f32 * percent) as i32,
@@ -95,115 +103,289 @@ fn render_defense_bar(ctx: &mut Context, location: (i32, i32), percent: f32) ->
     Ok(())
 }
 
-pub fn render_combat(ctx: &mut Context, world: &World) -> GameResult<()> {
+fn render_enemy_bars(list: &mut DrawList, atlas: &Atlas, enemy: &Spirit, enemy_bar: (i32, i32)) -> GameResult<()> {
+    render_health_bar(list, (
+        enemy_bar.0 + ENEMY_BAR_INNER_OFFSET.0,
+        enemy_bar.1 + ENEMY_BAR_INNER_OFFSET.1,
+    ), enemy.health as f32 / enemy.max_health as f32)?;
+    render_defense_bar(list, (
+        enemy_bar.0 + ENEMY_DEF_BAR_INNER_OFFSET.0,
+        enemy_bar.1 + ENEMY_DEF_BAR_INNER_OFFSET.1,
+    ), enemy.defense as f32 / 6.0)?;
+    list.sprite(enemy_bar_sprite(atlas, enemy_bar.0, enemy_bar.1, BAR_SIZE.0, BAR_SIZE.1));
+    Ok(())
+}
+
+fn floating_number(effect: &CombatEffect) -> (String, [f32; 4]) {
+    match effect {
+        CombatEffect::Damage(amount) => (format!("-{}", amount), [1.0, 0.3, 0.3, 1.0]),
+        CombatEffect::Heal(amount) => (format!("+{}", amount), [0.3, 1.0, 0.4, 1.0]),
+        CombatEffect::Defense(amount) => (format!("+{} DEF", amount), [0.4, 0.6, 1.0, 1.0]),
+        CombatEffect::ShedDefense(amount) => (format!("-{} DEF", amount), [0.6, 0.6, 0.7, 1.0]),
+    }
+}
+
+fn render_floating_number(list: &mut DrawList, number: &FloatingNumber, location: (i32, i32)) -> GameResult<()> {
+    let (text, color) = floating_number(&number.effect);
+    let progress = number.progress();
+    let alpha = (1.0 - (progress - (1.0 - FLOAT_FADE)).max(0.0) / FLOAT_FADE).max(0.0);
+    let x = (location.0 + SPRITE_SIZE.0 / 2 - text_width(&text) / 2) as f32;
+    let y = (location.1 + SPRITE_SIZE.1 / 2 - GLYPH_SIZE.1 + number.row as i32 * GLYPH_SIZE.1) as f32
+        - progress * FLOAT_RISE;
+    list.set_color([0.0, 0.0, 0.0, alpha].into())?;
+    list.text(&text, Point2::new(x + 1.0, y + 1.0))?;
+    list.set_color([color[0], color[1], color[2], alpha].into())?;
+    list.text(&text, Point2::new(x, y))?;
+    Ok(())
+}
+
+fn render_highlight(list: &mut DrawList, location: (i32, i32), size: (i32, i32), color: [f32; 4]) -> GameResult<()> {
+    list.set_color(color.into())?;
+    list.rectangle(DrawMode::Line(2.0), Rect::new_i32(
+        location.0 - 2,
+        location.1 - 2,
+        size.0 + 4,
+        size.1 + 4,
+    ))?;
+    Ok(())
+}
+
+// Fills the log area from the bottom up, starting at the scrolled-to entry.
+fn render_battle_log(list: &mut DrawList, battle_log: &BattleLog) -> GameResult<()> {
+    text_outline(list, LOG_AREA)?;
+    let width = LOG_AREA.2 - 16;
+    let capacity = ((LOG_AREA.3 - 16) / GLYPH_SIZE.1) as usize;
+    let newest = battle_log.entries.len().saturating_sub(battle_log.scroll);
+    let mut lines = Vec::new();
+    for (index, entry) in battle_log.entries[..newest].iter().enumerate().rev() {
+        if lines.len() >= capacity {
+            break;
+        }
+        let color = match index + 1 == battle_log.entries.len() {
+            true => [1.0, 1.0, 1.0, 1.0],
+            false => [0.7, 0.7, 0.7, 1.0],
+        };
+        lines.extend(wrap_spans(&parse_markup(entry, color), width).into_iter().rev());
+    }
+    lines.truncate(capacity);
+    lines.reverse();
+    render_lines(list, &lines, (LOG_AREA.0 + 8, LOG_AREA.1 + 8))?;
+    text_outline(list, NOTIFICATION_AREA)?;
+    text_in_box(list, &format!(
+        "Battle log, {} of {}. Up and Down scroll, L closes.",
+        newest, battle_log.entries.len(),
+    ), (
+        NOTIFICATION_AREA.0 + 8,
+        NOTIFICATION_AREA.1 + 8,
+        NOTIFICATION_AREA.2 - 16,
+    ))?;
+    Ok(())
+}
+
+pub fn render_combat(list: &mut DrawList, world: &World) -> GameResult<()> {
     let battle_state = world.read_resource::<BattleState>();
     let entities = world.read_resource::<EntitiesRes>();
     let spirits = world.write_storage::<Spirit>();
     let player_spirits = world.read_storage::<PlayerSpirit>();
-    let mut spritebatch = world.write_resource::<SpriteBatch>();
-    let mut enemy_count = 0;
-    let mut lineup_count = 0;
-    set_color(ctx, [0.7, 0.7, 0.8, 1.0].into())?;
-    rectangle(ctx, DrawMode::Fill, Rect::new_i32(
+    let bags = world.read_storage::<Bag>();
+    let animations = world.read_storage::<Animation>();
+    let atlas = world.read_resource::<Atlas>();
+    let mut locations = Vec::new();
+    list.set_color([0.7, 0.7, 0.8, 1.0].into())?;
+    list.rectangle(DrawMode::Fill, Rect::new_i32(
         0, 0,
         SCREEN_SIZE.0 as i32, SCREEN_SIZE.1 as i32,
     ))?;
-    for (entity, enemy, ()) in (&*entities, &spirits, !&player_spirits).join() {
-        if enemy_count < ENEMY_COUNT && enemy.health > 0 {
-            let enemy_bar = ENEMY_BARS[enemy_count];
-            let enemy_location = ENEMY_LOCATIONS[enemy_count];
-            render_health_bar(ctx, (
-                enemy_bar.0 + ENEMY_BAR_INNER_OFFSET.0,
-                enemy_bar.1 + ENEMY_BAR_INNER_OFFSET.1,
-            ), enemy.health as f32 / enemy.max_health as f32)?;
-            render_defense_bar(ctx, (
-                enemy_bar.0 + ENEMY_DEF_BAR_INNER_OFFSET.0,
-                enemy_bar.1 + ENEMY_DEF_BAR_INNER_OFFSET.1,
-            ), enemy.defense as f32 / 6.0)?;
-            spritebatch.add(enemy_bar_sprite(enemy_bar.0, enemy_bar.1, BAR_SIZE.0, BAR_SIZE.1));
-            spritebatch.add(battle_spirit_background(
-                enemy_location.0,
-                enemy_location.1,
-                SPRITE_SIZE.0,
-                SPRITE_SIZE.1,
-                None,
-            ));
-            spritebatch.add(spirit_sprite(
-                &enemy.element,
-                enemy_location.0,
-                enemy_location.1,
-                SPRITE_SIZE.0,
-                SPRITE_SIZE.1,
-                None,
-            ));
-            enemy_count += 1;
-        } else if lineup_count < LINEUP_COUNT {
-            let x_index = lineup_count % 4;
-            let y_index = lineup_count / 4;
-            let x_offset = x_index as i32 * ENEMY_LINEUP_SIZE.0;
-            let y_offset = y_index as i32 * ENEMY_LINEUP_SIZE.1;
-            let color: Option<Color> = match enemy.health {
-                0 => Some([0.5, 0.5, 0.5, 1.0].into()),
-                _ => None,
-            };
-            spritebatch.add(spirit_sprite(&enemy.element,
+    // Sprites always draw over text, so the log gets the screen to itself.
+    let battle_log = world.read_resource::<BattleLog>();
+    if battle_log.open {
+        return render_battle_log(list, &battle_log);
+    }
+    // The first living enemies stand at the front and take the hits, the rest wait in the lineup.
+    // Fainting enemies keep their place until they have faded out.
+    let enemies: Vec<(Entity, &Spirit)> = (&*entities, &spirits, !&player_spirits).join()
+        .map(|(entity, enemy, ())| (entity, enemy))
+        .collect();
+    let mut front = Vec::new();
+    let mut lineup = Vec::new();
+    for (index, (entity, enemy)) in enemies.iter().enumerate() {
+        let fading = animations.get(*entity).map_or(false, |animation| animation.is_fading());
+        if front.len() < ENEMY_COUNT && (enemy.health > 0 || fading) {
+            front.push(index);
+        } else {
+            lineup.push(index);
+        }
+    }
+    let targets = match (battle_state.item_menu, battle_state.get_move(&spirits)) {
+        (None, Some(combat_move)) => combat_move.effect.enemy_targets(),
+        _ => 0,
+    };
+    let mut living = 0;
+    for (slot, index) in front.iter().enumerate() {
+        let (entity, enemy) = enemies[*index];
+        let enemy_location = ENEMY_LOCATIONS[slot];
+        locations.push((entity, enemy_location));
+        render_enemy_bars(list, &atlas, enemy, ENEMY_BARS[slot])?;
+        if enemy.health > 0 {
+            living += 1;
+        }
+        if battle_state.roster == Some(*index) {
+            render_highlight(list, enemy_location, SPRITE_SIZE, ROSTER_COLOR)?;
+        } else if enemy.health > 0 && living <= targets {
+            render_highlight(list, enemy_location, SPRITE_SIZE, TARGET_COLOR)?;
+        }
+        list.sprite(battle_spirit_background(&atlas, 
+            enemy_location.0,
+            enemy_location.1,
+            SPRITE_SIZE.0,
+            SPRITE_SIZE.1,
+            None,
+        ));
+        let sprite = spirit_sprite(&atlas, 
+            &enemy.element,
+            enemy_location.0,
+            enemy_location.1,
+            SPRITE_SIZE.0,
+            SPRITE_SIZE.1,
+            None,
+        );
+        list.sprite(match animations.get(entity) {
+            Some(animation) => animated(sprite, animation.current(), 1),
+            None => sprite,
+        });
+    }
+    let pages = (lineup.len() + LINEUP_COUNT - 1) / LINEUP_COUNT;
+    let page = battle_state.roster
+        .and_then(|roster| lineup.iter().position(|index| *index == roster))
+        .map(|position| position / LINEUP_COUNT)
+        .unwrap_or(0);
+    for (lineup_count, index) in lineup.iter().skip(page * LINEUP_COUNT).take(LINEUP_COUNT).enumerate() {
+        let (_entity, enemy) = enemies[*index];
+        let x_index = lineup_count % 4;
+        let y_index = lineup_count / 4;
+        let x_offset = x_index as i32 * ENEMY_LINEUP_SIZE.0;
+        let y_offset = y_index as i32 * ENEMY_LINEUP_SIZE.1;
+        let color: Option<Color> = match enemy.health {
+            0 => Some([0.5, 0.5, 0.5, 1.0].into()),
+            _ => None,
+        };
+        if battle_state.roster == Some(*index) {
+            render_highlight(list, (
                 ENEMY_LINEUP.0 + x_offset + 4, ENEMY_LINEUP.1 + y_offset + 4,
+            ), (
                 ENEMY_LINEUP_SIZE.0 - 8, ENEMY_LINEUP_SIZE.1 - 8,
-                color,
-            ));
-            lineup_count += 1;
+            ), ROSTER_COLOR)?;
         }
+        list.sprite(spirit_sprite(&atlas, &enemy.element,
+            ENEMY_LINEUP.0 + x_offset + 4, ENEMY_LINEUP.1 + y_offset + 4,
+            ENEMY_LINEUP_SIZE.0 - 8, ENEMY_LINEUP_SIZE.1 - 8,
+            color,
+        ));
+    }
+    if pages > 1 {
+        list.set_color([0.0, 0.0, 0.0, 1.0].into())?;
+        list.text(
+            &format!("Lineup {}/{}", page + 1, pages),
+            Point2::new(LINEUP_PAGE.0, LINEUP_PAGE.1),
+        )?;
+    }
+    let roster_enemy = battle_state.roster
+        .and_then(|index| enemies.get(index))
+        .map(|(_entity, enemy)| *enemy);
+    if let Some(enemy) = roster_enemy {
+        render_enemy_bars(list, &atlas, enemy, ROSTER_BAR)?;
     }
     let mut ally_count = 0;
     for (entity, ally, player) in (&*entities, &spirits, &player_spirits).join() {
         if player.active {
             let ally_bar = ALLY_BARS[ally_count];
-            render_health_bar(ctx, (
+            render_health_bar(list, (
                 ally_bar.0 + ALLY_BAR_INNER_OFFSET.0,
                 ally_bar.1 + ALLY_BAR_INNER_OFFSET.1,
             ), ally.health as f32 / ally.max_health as f32)?;
-            render_defense_bar(ctx, (
+            render_defense_bar(list, (
                 ally_bar.0 + ALLY_DEF_BAR_INNER_OFFSET.0,
                 ally_bar.1 + ALLY_DEF_BAR_INNER_OFFSET.1,
             ), ally.defense as f32 / 6.0)?;
-            spritebatch.add(ally_bar_sprite(ally_bar.0, ally_bar.1, BAR_SIZE.0, BAR_SIZE.1));
-            spritebatch.add(battle_spirit_background(
+            list.sprite(ally_bar_sprite(&atlas, ally_bar.0, ally_bar.1, BAR_SIZE.0, BAR_SIZE.1));
+            list.sprite(battle_spirit_background(&atlas, 
                 ALLY_LOCATION.0,
                 ALLY_LOCATION.1,
                 SPRITE_SIZE.0,
                 SPRITE_SIZE.1,
                 None,
             ));
-            spritebatch.add(spirit_sprite(
+            let sprite = spirit_sprite(&atlas, 
                 &ally.element,
                 ALLY_LOCATION.0,
                 ALLY_LOCATION.1,
                 SPRITE_SIZE.0,
                 SPRITE_SIZE.1,
                 None,
-            ));
+            );
+            list.sprite(match animations.get(entity) {
+                Some(animation) => animated(sprite, animation.current(), -1),
+                None => sprite,
+            });
+            locations.push((entity, ALLY_LOCATION));
             ally_count += 1;
         }
     }
-    let font = Font::default_font()?;
-    match (battle_state.active_entity, battle_state.combat_move) {
-        (Some(entity), Some(index)) => {
+    let location_of = |target: &Entity| locations.iter()
+        .find(|(entity, _location)| entity == target)
+        .map(|(_entity, location)| *location);
+    if let Some(ref effect) = battle_state.attack_effect {
+        if let Some(from) = location_of(&effect.attacker) {
+            for to in effect.targets.iter().filter_map(|target| location_of(target)) {
+                list.sprite(attack_effect_sprite(&atlas, effect, from, to, SPRITE_SIZE.0));
+            }
+        }
+    }
+    let floating_numbers = world.read_resource::<FloatingNumbers>();
+    if !floating_numbers.numbers.is_empty() {
+        list.new_layer();
+        for number in floating_numbers.numbers.iter() {
+            if let Some(location) = location_of(&number.target) {
+                render_floating_number(list, number, location)?;
+            }
+        }
+    }
+    let mut items = Vec::new();
+    for bag in (&bags).join() {
+        items = bag.battle_items();
+    }
+    match (battle_state.active_entity, battle_state.combat_move, battle_state.item_menu) {
+        (Some(_), _, Some(index)) => {
+            text_outline(list, MOVE_REGION)?;
+            let page = index / 4;
+            for slot in 0..4 {
+                if let Some((item, count)) = items.get(page * 4 + slot) {
+                    if index == page * 4 + slot {
+                        list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
+                    } else {
+                        list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+                    }
+                    list.text(
+                        &format!("{} x{}", item_name(item), count),
+                        Point2::new(MOVE_AREAS[slot].0, MOVE_AREAS[slot].1),
+                    )?;
+                }
+            }
+        },
+        (Some(entity), Some(index), None) => {
             match spirits.get(entity) {
                 Some(spirit) => {
-                    text_outline(ctx, MOVE_REGION)?;
+                    text_outline(list, MOVE_REGION)?;
                     for move_index in 0..4 {
                         let combat_move = &spirit.moves[move_index];
-                        let text = &Text::new(ctx, &combat_move.name, &font)?;
                         if index == move_index {
-                            set_color(ctx, [0.0, 0.0, 1.0, 1.0].into())?;
+                            list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
                         } else {
-                            set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
+                            list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
                         }
-                        draw(
-                            ctx,
-                            text,
+                        list.text(
+                            &combat_move.name,
                             Point2::new(MOVE_AREAS[move_index].0, MOVE_AREAS[move_index].1),
-                            0.0,
                         )?;
                     }
                 },
@@ -212,17 +394,26 @@ pub fn render_combat(ctx: &mut Context, world: &World) -> GameResult<()> {
         },
         _ => {}
     }
-    text_outline(ctx, NOTIFICATION_AREA)?;
+    text_outline(list, NOTIFICATION_AREA)?;
     let text_area = (
         NOTIFICATION_AREA.0 + 8,
         NOTIFICATION_AREA.1 + 8,
         NOTIFICATION_AREA.2 - 16,
     );
+    let selected_item = battle_state.item_menu.and_then(|index| items.get(index));
     if let Some(ref notification) = battle_state.notification {
-        text_in_box(ctx, notification, text_area)?;
+        text_in_box_revealed(list, notification, text_area, battle_state.notification_shown())?;
+    } else if let (Some(index), Some(enemy)) = (battle_state.roster, roster_enemy) {
+        text_in_box(list, &roster_text(enemy, index, enemies.len()), text_area)?;
+    } else if battle_state.item_menu.is_some() {
+        let text = match selected_item {
+            Some((item, _count)) => item_text(item),
+            None => format!("You have no items to use"),
+        };
+        text_in_box(list, &text, text_area)?;
     } else if let Some(combat_move) = battle_state.get_move(&spirits) {
         let text = &move_text(&combat_move);
-        text_in_box(ctx, text, text_area)?;
+        text_in_box(list, text, text_area)?;
     }
     Ok(())
 }
@@ -0,0 +1,99 @@
+use ggez::*;
+use specs::*;
+use ggez::graphics::*;
+use state::*;
+use render::*;
+
+const PORTRAIT: (i32, i32, i32, i32) = (
+    16, 16,
+    128, 128,
+);
+const SUMMARY_AREA: (i32, i32, i32, i32) = (
+    160, 8,
+    SCREEN_SIZE.0 as i32 - 168, 144,
+);
+const HISTORY_AREA: (i32, i32, i32, i32) = (
+    8, 160,
+    SCREEN_SIZE.0 as i32 - 16, 72,
+);
+const MOVES_AREA: (i32, i32, i32, i32) = (
+    8, 240,
+    SCREEN_SIZE.0 as i32 - 16, 136,
+);
+const MOVE_SIZE: (i32, i32) = (
+    (SCREEN_SIZE.0 as i32 - 16) / 2, 64,
+);
+const DETAILS_INFO: (i32, i32, i32, i32) = (
+    0, SCREEN_SIZE.1 as i32 - 64,
+    SCREEN_SIZE.0 as i32, 64,
+);
+
+pub fn render_details(list: &mut DrawList, world: &mut World, index: usize, naming: bool) -> GameResult<()> {
+    type SystemData<'a> = (
+        ReadStorage<'a, Player>,
+        ReadExpect<'a, InventoryState>,
+        ReadExpect<'a, Atlas>,
+    );
+    world.exec(|(players, inventory_state, atlas): SystemData| -> GameResult<()> {
+        for player in (&players).join() {
+            let spirit = match inventory_state.viewing_storage {
+                true => player.storage.get(index),
+                false => player.spirits.get(index),
+            };
+            let spirit = match spirit {
+                Some(spirit) => spirit,
+                None => continue,
+            };
+            list.sprite(spirit_sprite(&atlas, &spirit.element,
+                PORTRAIT.0,
+                PORTRAIT.1,
+                PORTRAIT.2,
+                PORTRAIT.3,
+                None,
+            ));
+            text_outline(list, SUMMARY_AREA)?;
+            text_in_box(list, &format!("{}\n{}\nHealth: {}\nAttack: {} Stamina: {} Defense: {}\n{}",
+                escape_markup(&display_name(spirit)),
+                spirit_level_text(&spirit.element),
+                health(spirit),
+                spirit.attack,
+                spirit.stamina,
+                spirit.base_defense,
+                iv_text(spirit),
+            ), (
+                SUMMARY_AREA.0 + 8,
+                SUMMARY_AREA.1 + 8,
+                SUMMARY_AREA.2 - 16,
+            ))?;
+            text_outline(list, HISTORY_AREA)?;
+            text_in_box(list, &format!("Battles won: {}\nDamage dealt: {}\n{}",
+                spirit.wins,
+                spirit.damage_dealt,
+                origin_text(spirit.origin_depth),
+            ), (
+                HISTORY_AREA.0 + 8,
+                HISTORY_AREA.1 + 8,
+                HISTORY_AREA.2 - 16,
+            ))?;
+            text_outline(list, MOVES_AREA)?;
+            for (idx, combat_move) in spirit.moves.iter().enumerate() {
+                text_in_box(list, &move_text(combat_move), (
+                    MOVES_AREA.0 + 8 + MOVE_SIZE.0 * (idx as i32 % 2),
+                    MOVES_AREA.1 + 8 + MOVE_SIZE.1 * (idx as i32 / 2),
+                    MOVE_SIZE.0 - 16,
+                ))?;
+            }
+            text_outline(list, DETAILS_INFO)?;
+            let info = match naming {
+                true => format!("Nickname: {}_\nEnter: confirm. Escape: cancel.", inventory_state.name_entry),
+                false => format!("Space: give a nickname. Backspace: back."),
+            };
+            text_in_box(list, &info, (
+                DETAILS_INFO.0 + 8,
+                DETAILS_INFO.1 + 8,
+                DETAILS_INFO.2 - 16,
+            ))?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,191 @@
+use ggez::*;
+use ggez::graphics::*;
+use ggez::graphics::spritebatch::*;
+use render::{printable, GLYPH_SIZE};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Rectangle {
+        fill: bool,
+        line_width: f32,
+        rect: [f32; 4],
+        color: [f32; 4],
+    },
+    Sprite {
+        src: [f32; 4],
+        dest: [f32; 2],
+        scale: [f32; 2],
+        color: Option<[f32; 4]>,
+    },
+    Text {
+        text: String,
+        position: [f32; 2],
+        color: [f32; 4],
+    },
+    Layer,
+}
+
+fn color_array(color: Color) -> [f32; 4] {
+    [color.r, color.g, color.b, color.a]
+}
+
+// Screens describe a frame here instead of talking to ggez, so the frame can be drawn or recorded.
+pub struct DrawList {
+    commands: Vec<DrawCommand>,
+    color: [f32; 4],
+}
+
+impl DrawList {
+    pub fn new() -> Self {
+        DrawList {
+            commands: Vec::new(),
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+    pub fn commands(&self) -> &Vec<DrawCommand> {
+        &self.commands
+    }
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+    pub fn set_color(&mut self, color: Color) -> GameResult<()> {
+        self.color = color_array(color);
+        Ok(())
+    }
+    pub fn rectangle(&mut self, mode: DrawMode, rect: Rect) -> GameResult<()> {
+        let (fill, line_width) = match mode {
+            DrawMode::Fill => (true, 0.0),
+            DrawMode::Line(width) => (false, width),
+        };
+        self.commands.push(DrawCommand::Rectangle {
+            fill,
+            line_width,
+            rect: [rect.x, rect.y, rect.w, rect.h],
+            color: self.color,
+        });
+        Ok(())
+    }
+    pub fn text(&mut self, text: &str, position: Point2) -> GameResult<()> {
+        self.commands.push(DrawCommand::Text {
+            text: text.to_string(),
+            position: [position.x, position.y],
+            color: self.color,
+        });
+        Ok(())
+    }
+    // Sprites normally land on top of everything else. Whatever is added after a new layer
+    // is drawn over the sprites added before it.
+    pub fn new_layer(&mut self) {
+        self.commands.push(DrawCommand::Layer);
+    }
+    pub fn sprite(&mut self, param: DrawParam) {
+        self.commands.push(DrawCommand::Sprite {
+            src: [param.src.x, param.src.y, param.src.w, param.src.h],
+            dest: [param.dest.x, param.dest.y],
+            scale: [param.scale.x, param.scale.y],
+            color: param.color.map(color_array),
+        });
+    }
+}
+
+pub trait RenderBackend {
+    fn render(&mut self, list: &DrawList) -> GameResult<()>;
+}
+
+pub struct GgezBackend<'a> {
+    ctx: &'a mut Context,
+    spritebatch: &'a mut SpriteBatch,
+    font: &'a Font,
+}
+
+impl<'a> GgezBackend<'a> {
+    pub fn new(ctx: &'a mut Context, spritebatch: &'a mut SpriteBatch, font: &'a Font) -> Self {
+        GgezBackend {
+            ctx,
+            spritebatch,
+            font,
+        }
+    }
+    fn flush_sprites(&mut self) -> GameResult<()> {
+        set_color(self.ctx, [1.0, 1.0, 1.0, 1.0].into())?;
+        draw(self.ctx, &*self.spritebatch, Point2::new(0.0, 0.0), 0.0)?;
+        self.spritebatch.clear();
+        Ok(())
+    }
+}
+
+impl<'a> RenderBackend for GgezBackend<'a> {
+    fn render(&mut self, list: &DrawList) -> GameResult<()> {
+        self.spritebatch.clear();
+        for command in list.commands().iter() {
+            match command {
+                DrawCommand::Rectangle { fill, line_width, rect, color } => {
+                    set_color(self.ctx, (*color).into())?;
+                    let mode = match fill {
+                        true => DrawMode::Fill,
+                        false => DrawMode::Line(*line_width),
+                    };
+                    rectangle(self.ctx, mode, Rect::new(rect[0], rect[1], rect[2], rect[3]))?;
+                },
+                DrawCommand::Text { text, position, color } => {
+                    set_color(self.ctx, (*color).into())?;
+                    for (row, line) in text.split('\n').enumerate() {
+                        if line.is_empty() {
+                            continue;
+                        }
+                        let text = Text::new(self.ctx, &printable(line), self.font)?;
+                        let y = position[1] + (row as i32 * GLYPH_SIZE.1) as f32;
+                        draw(self.ctx, &text, Point2::new(position[0], y), 0.0)?;
+                    }
+                },
+                DrawCommand::Sprite { src, dest, scale, color } => {
+                    self.spritebatch.add(DrawParam {
+                        src: Rect::new(src[0], src[1], src[2], src[3]),
+                        dest: Point2::new(dest[0], dest[1]),
+                        scale: Point2::new(scale[0], scale[1]),
+                        color: color.map(|color| color.into()),
+                        ..Default::default()
+                    });
+                },
+                DrawCommand::Layer => self.flush_sprites()?,
+            }
+        }
+        // Sprites are batched and land on top of the shapes and text of their layer.
+        self.flush_sprites()
+    }
+}
+
+// Keeps frames instead of drawing them, so tests can compare layouts without a window.
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingBackend {
+    pub frames: Vec<Vec<DrawCommand>>,
+}
+
+#[cfg(test)]
+impl RecordingBackend {
+    pub fn new() -> Self {
+        RecordingBackend::default()
+    }
+    pub fn last_frame(&self) -> Option<&Vec<DrawCommand>> {
+        self.frames.last()
+    }
+    // One command per line, so a changed layout shows up as a readable diff.
+    pub fn snapshot(&self) -> String {
+        match self.last_frame() {
+            Some(frame) => frame.iter()
+                .map(|command| format!("{:?}", command))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            None => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl RenderBackend for RecordingBackend {
+    fn render(&mut self, list: &DrawList) -> GameResult<()> {
+        self.frames.push(list.commands().clone());
+        Ok(())
+    }
+}
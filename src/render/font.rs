@@ -0,0 +1,24 @@
+use ggez::*;
+use ggez::graphics::*;
+
+pub const FONT_FILE: &str = "/font.png";
+// Every glyph in font.png, left to right. Each one is GLYPH_SIZE pixels.
+pub const GLYPHS: &str = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+pub const GLYPH_SIZE: (i32, i32) = (8, 16);
+const MISSING_GLYPH: char = '?';
+
+pub fn load_font(ctx: &mut Context) -> GameResult<Font> {
+    Font::new_bitmap(ctx, FONT_FILE, GLYPHS)
+}
+
+// The font is monospaced, so measuring text needs no context.
+pub fn text_width(text: &str) -> i32 {
+    text.chars().count() as i32 * GLYPH_SIZE.0
+}
+
+// The bitmap font refuses characters it has no glyph for, so those are swapped out.
+pub fn printable(text: &str) -> String {
+    text.chars()
+        .map(|c| if GLYPHS.contains(c) { c } else { MISSING_GLYPH })
+        .collect()
+}
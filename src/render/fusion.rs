@@ -0,0 +1,95 @@
+use ggez::*;
+use specs::*;
+use ggez::graphics::*;
+use state::*;
+use render::*;
+
+const FUSION_HEADER: (f32, f32) = (
+    8.0, 8.0,
+);
+const CONSUMED_REGION: (f32, f32, f32, f32) = (
+    8.0, 32.0,
+    SCREEN_SIZE.0 as f32 - 16.0, 160.0,
+);
+const RESULT_HEADER: (f32, f32) = (
+    8.0, 200.0,
+);
+const RESULT_SPRITE: (i32, i32, i32, i32) = (
+    8, 224,
+    96, 96,
+);
+const RESULT_AREA: (i32, i32, i32, i32) = (
+    120, 224,
+    SCREEN_SIZE.0 as i32 - 128, 136,
+);
+const FUSION_INFO: (i32, i32, i32, i32) = (
+    0, SCREEN_SIZE.1 as i32 - 80,
+    SCREEN_SIZE.0 as i32, 80,
+);
+
+fn stat_change(label: &str, before: i64, after: i64) -> String {
+    format!("{}: {} -> {}", label, before, after)
+}
+
+pub fn render_fusing(list: &mut DrawList, world: &mut World, index: usize) -> GameResult<()> {
+    type SystemData<'a> = (
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Bag>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Difficulty>,
+        ReadExpect<'a, Atlas>,
+    );
+    world.exec(|(players, bags, settings, difficulty, atlas): SystemData| -> GameResult<()> {
+        for (player, bag) in (&players, &bags).join() {
+            let plan = match plan_fusion(&player.spirits, bag.count(&ItemType::Catalyst), index, &settings.inheritance, &difficulty) {
+                Some(plan) => plan,
+                None => continue,
+            };
+            let target = &player.spirits[index];
+            let consumed: Vec<SpiritType> = plan.consumed.iter()
+                .map(|idx| player.spirits[*idx].element.clone())
+                .collect();
+            list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+            list.text(&"These spirits will be consumed", Point2::new(
+                FUSION_HEADER.0,
+                FUSION_HEADER.1,
+            ))?;
+            render_spread(list, &atlas, &consumed, CONSUMED_REGION)?;
+            list.text(&"Result", Point2::new(
+                RESULT_HEADER.0,
+                RESULT_HEADER.1,
+            ))?;
+            list.sprite(spirit_sprite(&atlas, &plan.result.element,
+                RESULT_SPRITE.0,
+                RESULT_SPRITE.1,
+                RESULT_SPRITE.2,
+                RESULT_SPRITE.3,
+                None,
+            ));
+            text_outline(list, RESULT_AREA)?;
+            text_in_box(list, &format!("{}\n{}\n{}\n{}\n{}\n{}",
+                spirit_level_text(&plan.result.element),
+                stat_change("Max health", target.max_health as i64, plan.result.max_health as i64),
+                stat_change("Attack", target.attack as i64, plan.result.attack as i64),
+                stat_change("Stamina", target.stamina as i64, plan.result.stamina as i64),
+                stat_change("Defense", target.base_defense as i64, plan.result.base_defense as i64),
+                iv_text(&plan.result),
+            ), (
+                RESULT_AREA.0 + 8,
+                RESULT_AREA.1 + 8,
+                RESULT_AREA.2 - 16,
+            ))?;
+            text_outline(list, FUSION_INFO)?;
+            let catalyst = match plan.catalyst {
+                true => format!("A {} will be used.\n", item_name(&ItemType::Catalyst)),
+                false => String::new(),
+            };
+            text_in_box(list, &format!("{}Space: fuse. Backspace: cancel.", catalyst), (
+                FUSION_INFO.0 + 8,
+                FUSION_INFO.1 + 8,
+                FUSION_INFO.2 - 16,
+            ))?;
+        }
+        Ok(())
+    })
+}
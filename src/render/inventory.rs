@@ -1,7 +1,6 @@
 use ggez::*;
 use specs::*;
 use ggez::graphics::*;
-use ggez::graphics::spritebatch::*;
 use state::*;
 use render::*;
 
@@ -10,11 +9,19 @@ const SPIRIT_LOCATION: (i32, i32) = (8, 8);
 const SPIRIT_BUFFER: (i32, i32) = (8, 8);
 const INVENTORY_LAYOUT: (i32, i32) = (5, 5);
 const DESCRIPTION_AREA: (i32, i32) = (368, 8);
-const DESCRIPTION_SIZE: (i32, i32) = (256, 280);
+const DESCRIPTION_SIZE: (i32, i32) = (256, 256);
 const NAME_OFFSET: (f32, f32) = (8.0, 8.0);
 const ELEMENT_OFFSET: (f32, f32) = (8.0, 24.0);
 const HEALTH_OFFSET: (f32, f32) = (8.0, 40.0);
 const COLLIDE_OFFSET: (f32, f32) = (8.0, 72.0);
+const HEALTH_BAR_OFFSET: (i32, i32) = (8, SPIRIT_SIZE.1 - 10);
+const HEALTH_BAR_SIZE: (i32, i32) = (SPIRIT_SIZE.0 - 16, 4);
+const LOCK_OFFSET: (i32, i32) = (SPIRIT_SIZE.0 - 14, 6);
+const LOCK_SIZE: (i32, i32) = (8, 8);
+const PARTY_SUMMARY_AREA: (i32, i32, i32, i32) = (
+    DESCRIPTION_AREA.0, DESCRIPTION_AREA.1 + DESCRIPTION_SIZE.1 + 8,
+    DESCRIPTION_SIZE.0, 88,
+);
 const MOVES_OFFSETS: [(i32, i32, i32); 4] = [
     (DESCRIPTION_AREA.0 + 8, 136, 130),
     (DESCRIPTION_AREA.0 + 148, 136, 130),
@@ -22,100 +29,129 @@ const MOVES_OFFSETS: [(i32, i32, i32); 4] = [
     (DESCRIPTION_AREA.0 + 148, 200, 130),
 ];
 
-pub fn render_inventory(ctx: &mut Context, world: &mut World, is_retreat: bool) -> GameResult<()> {
+pub fn render_inventory(list: &mut DrawList, world: &mut World, is_retreat: bool) -> GameResult<()> {
     type SystemData<'a> = (
         ReadStorage<'a, Player>,
         ReadExpect<'a, InventoryState>,
-        WriteExpect<'a, SpriteBatch>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Difficulty>,
         Entities<'a>,
         ReadStorage<'a, Spirit>,
         ReadStorage<'a, PlayerSpirit>,
+        ReadExpect<'a, Atlas>,
     );
-    world.exec(|(players, inventory_state, mut spritebatch, entities, spirits, player_spirits): SystemData| -> GameResult<()> {
-        let font = Font::default_font()?;
+    world.exec(|(players, inventory_state, settings, difficulty, entities, spirits, player_spirits, atlas): SystemData| -> GameResult<()> {
         for player in (&players).join() {
-            let mut inv_spirits = player.spirits.clone();
+            let mut inv_spirits = match inventory_state.viewing_storage {
+                true => player.storage.clone(),
+                false => player.spirits.clone(),
+            };
             if is_retreat {
                 inv_spirits = Vec::new();
                 for (entity, spirit, player_spirit) in (&*entities, &spirits, &player_spirits).join() {
                     inv_spirits.push(spirit.clone());
                 }
             }
+            let page_offset = (inventory_state.page() * INVENTORY_PAGE_SIZE) as i32;
+            let health_total: u32 = inv_spirits.iter().map(|spirit| spirit.health).sum();
+            let max_health_total: u32 = inv_spirits.iter().map(|spirit| spirit.max_health).sum();
+            text_outline(list, PARTY_SUMMARY_AREA)?;
+            text_in_box(list, &format!("{}: page {}, {}\nParty {}/{} Box {} Fainted {}\nHealth: {} / {}\n{}",
+                match inventory_state.viewing_storage {
+                    true => "Storage box",
+                    false => "Party",
+                },
+                inventory_state.page() + 1,
+                sort_text(&inventory_state.sort_mode),
+                player.spirits.len(),
+                settings.party_size,
+                player.storage.len(),
+                player.fainted.len(),
+                health_total,
+                max_health_total,
+                match inventory_state.releasing {
+                    Some(_) => "Press R again to release",
+                    None => "",
+                },
+            ), (
+                PARTY_SUMMARY_AREA.0 + 8,
+                PARTY_SUMMARY_AREA.1 + 8,
+                PARTY_SUMMARY_AREA.2 - 16,
+            ))?;
             for y in 0..INVENTORY_LAYOUT.0 {
                 for x in 0..INVENTORY_LAYOUT.1 {
-                    let index = x + y * INVENTORY_LAYOUT.0;
+                    let index = page_offset + x + y * INVENTORY_LAYOUT.0;
                     if index == inventory_state.index as i32 {
-                        set_color(ctx, [0.0, 0.0, 1.0, 1.0].into())?;
+                        list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
                     } else {
-                        set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
+                        list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
                     }
-                    rectangle(ctx, DrawMode::Line(4.0), Rect::new_i32(
+                    list.rectangle(DrawMode::Line(4.0), Rect::new_i32(
                         SPIRIT_LOCATION.0 + (SPIRIT_SIZE.0 + SPIRIT_BUFFER.0) * x,
                         SPIRIT_LOCATION.1 + (SPIRIT_SIZE.1 + SPIRIT_BUFFER.1) * y,
                         SPIRIT_SIZE.0,
                         SPIRIT_SIZE.1,
                     ))?;
                     if let Some(spirit) = inv_spirits.get(index as usize) {
-                        set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
-                        spritebatch.add(spirit_sprite(&spirit.element,
+                        list.set_color([1.0, 0.0, 0.0, 1.0].into())?;
+                        list.rectangle(DrawMode::Fill, Rect::new_i32(
+                            SPIRIT_LOCATION.0 + (SPIRIT_SIZE.0 + SPIRIT_BUFFER.0) * x + HEALTH_BAR_OFFSET.0,
+                            SPIRIT_LOCATION.1 + (SPIRIT_SIZE.1 + SPIRIT_BUFFER.1) * y + HEALTH_BAR_OFFSET.1,
+                            (HEALTH_BAR_SIZE.0 as f32 * spirit.health as f32 / spirit.max_health as f32) as i32,
+                            HEALTH_BAR_SIZE.1,
+                        ))?;
+                        if spirit.locked {
+                            list.set_color([1.0, 0.8, 0.0, 1.0].into())?;
+                            list.rectangle(DrawMode::Fill, Rect::new_i32(
+                                SPIRIT_LOCATION.0 + (SPIRIT_SIZE.0 + SPIRIT_BUFFER.0) * x + LOCK_OFFSET.0,
+                                SPIRIT_LOCATION.1 + (SPIRIT_SIZE.1 + SPIRIT_BUFFER.1) * y + LOCK_OFFSET.1,
+                                LOCK_SIZE.0,
+                                LOCK_SIZE.1,
+                            ))?;
+                        }
+                        list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+                        list.sprite(spirit_sprite(&atlas, &spirit.element,
                             SPIRIT_LOCATION.0 + (SPIRIT_SIZE.0 + SPIRIT_BUFFER.0) * x,
                             SPIRIT_LOCATION.1 + (SPIRIT_SIZE.1 + SPIRIT_BUFFER.1) * y,
                             SPIRIT_SIZE.0,
                             SPIRIT_SIZE.1,
                             None,
                         ));
-                        set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
-                        rectangle(ctx, DrawMode::Line(4.0), Rect::new_i32(
+                        list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+                        list.rectangle(DrawMode::Line(4.0), Rect::new_i32(
                             DESCRIPTION_AREA.0,
                             DESCRIPTION_AREA.1,
                             DESCRIPTION_SIZE.0,
                             DESCRIPTION_SIZE.1,
                         ))?;
                         if index == inventory_state.index as i32 {
-                            set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
-                            let text = Text::new(
-                                ctx,
-                                &spirit_name(&spirit.element),
-                                &font,
-                            )?;
-                            draw(ctx, &text, Point2::new(
+                            list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+                            list.text(&match spirit.locked {
+                                    true => format!("{} (Locked)", display_name(&spirit)),
+                                    false => display_name(&spirit),
+                                }, Point2::new(
                                 DESCRIPTION_AREA.0 as f32 + NAME_OFFSET.0,
                                 DESCRIPTION_AREA.1 as f32 + NAME_OFFSET.1,
-                            ), 0.0)?;
-                            let text = Text::new(
-                                ctx,
-                                &spirit_level_text(&spirit.element),
-                                &font,
-                            )?;
-                            draw(ctx, &text, Point2::new(
+                            ))?;
+                            list.text(&spirit_level_text(&spirit.element), Point2::new(
                                 DESCRIPTION_AREA.0 as f32 + ELEMENT_OFFSET.0,
                                 DESCRIPTION_AREA.1 as f32 + ELEMENT_OFFSET.1,
-                            ), 0.0)?;
-                            let text = Text::new(
-                                ctx,
-                                &format!("Health: {}", health(&spirit)),
-                                &font,
-                            )?;
-                            draw(ctx, &text, Point2::new(
+                            ))?;
+                            list.text(&format!("Health: {}", health(&spirit)), Point2::new(
                                 DESCRIPTION_AREA.0 as f32 + HEALTH_OFFSET.0,
                                 DESCRIPTION_AREA.1 as f32 + HEALTH_OFFSET.1,
-                            ), 0.0)?;
-                            let text = Text::new(
-                                ctx,
-                                &iv_text(&spirit),
-                                &font,
-                            )?;
-                            draw(ctx, &text, Point2::new(
+                            ))?;
+                            list.text(&iv_text(&spirit), Point2::new(
                                 DESCRIPTION_AREA.0 as f32 + HEALTH_OFFSET.0 + 16.0,
                                 DESCRIPTION_AREA.1 as f32 + HEALTH_OFFSET.1 + 16.0,
-                            ), 0.0)?;
-                            text_in_box(ctx, &collide_text(&spirit.element), (
+                            ))?;
+                            text_in_box(list, &collide_text(&spirit.element, &difficulty), (
                                 DESCRIPTION_AREA.0 + COLLIDE_OFFSET.0 as i32,
                                 DESCRIPTION_AREA.1 + COLLIDE_OFFSET.1 as i32,
                                 DESCRIPTION_SIZE.0,
                             ))?;
                             for (idx, combat_move) in spirit.moves.iter().enumerate() {
-                                text_in_box(ctx, &move_text(&combat_move), (
+                                text_in_box(list, &move_text(&combat_move), (
                                     MOVES_OFFSETS[idx].0,
                                     MOVES_OFFSETS[idx].1,
                                     MOVES_OFFSETS[idx].2,
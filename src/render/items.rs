@@ -0,0 +1,83 @@
+use ggez::*;
+use specs::*;
+use ggez::graphics::*;
+use state::*;
+use render::*;
+
+const ITEM_LIST_AREA: (i32, i32, i32, i32) = (
+    0, 0,
+    SCREEN_SIZE.0 as i32 / 2, SCREEN_SIZE.1 as i32,
+);
+const ITEM_LINE_HEIGHT: i32 = 24;
+const TARGET_AREA: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2, 0,
+    SCREEN_SIZE.0 as i32 / 2, 96,
+);
+const TARGET_SPRITE_SIZE: (i32, i32) = (64, 64);
+const ITEM_DESCRIPTION_AREA: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2, 96,
+    SCREEN_SIZE.0 as i32 / 2, SCREEN_SIZE.1 as i32 - 96,
+);
+
+pub fn render_items(list: &mut DrawList, world: &mut World, index: usize) -> GameResult<()> {
+    type SystemData<'a> = (
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Bag>,
+        ReadExpect<'a, InventoryState>,
+        ReadExpect<'a, Atlas>,
+    );
+    world.exec(|(players, bags, inventory_state, atlas): SystemData| -> GameResult<()> {
+        for (player, bag) in (&players, &bags).join() {
+            text_outline(list, ITEM_LIST_AREA)?;
+            if bag.items.len() == 0 {
+                text_in_box(list, &format!("Your bag is empty"), (
+                    ITEM_LIST_AREA.0 + 16,
+                    ITEM_LIST_AREA.1 + 16,
+                    ITEM_LIST_AREA.2 - 32,
+                ))?;
+            }
+            for (idx, (item, count)) in bag.items.iter().enumerate() {
+                if idx == index {
+                    list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
+                } else {
+                    list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+                }
+                list.text(&format!("{} x{}", item_name(item), count), Point2::new(
+                    (ITEM_LIST_AREA.0 + 16) as f32,
+                    (ITEM_LIST_AREA.1 + 16 + ITEM_LINE_HEIGHT * idx as i32) as f32,
+                ))?;
+            }
+            text_outline(list, TARGET_AREA)?;
+            if let Some(spirit) = player.spirits.get(inventory_state.index) {
+                list.sprite(spirit_sprite(&atlas, &spirit.element,
+                    TARGET_AREA.0 + 16,
+                    TARGET_AREA.1 + 16,
+                    TARGET_SPRITE_SIZE.0,
+                    TARGET_SPRITE_SIZE.1,
+                    None,
+                ));
+                text_in_box(list, &format!("{}\nHealth: {}",
+                    escape_markup(&display_name(&spirit)),
+                    health(&spirit),
+                ), (
+                    TARGET_AREA.0 + 32 + TARGET_SPRITE_SIZE.0,
+                    TARGET_AREA.1 + 24,
+                    TARGET_AREA.2 - 48 - TARGET_SPRITE_SIZE.0,
+                ))?;
+            }
+            text_outline(list, ITEM_DESCRIPTION_AREA)?;
+            if let Some((item, _count)) = bag.items.get(index) {
+                let usable = match item.usable_in_world() {
+                    true => "Press Space to use it on the spirit above.",
+                    false => "It can't be used here.",
+                };
+                text_in_box(list, &format!("{}\n{}", item_text(item), usable), (
+                    ITEM_DESCRIPTION_AREA.0 + 16,
+                    ITEM_DESCRIPTION_AREA.1 + 16,
+                    ITEM_DESCRIPTION_AREA.2 - 32,
+                ))?;
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,72 @@
+use ggez::*;
+use ggez::graphics::*;
+use state::*;
+
+// Every layout constant is in virtual pixels, SCREEN_SIZE wide and tall.
+// The viewport maps that virtual screen onto the real window at a whole-number
+// scale so sprites stay pixel-perfect, centring it with black bars around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Viewport {
+    pub window: (u32, u32),
+    pub scale: u32,
+    pub offset: (u32, u32),
+    pub fullscreen: bool,
+}
+
+impl Viewport {
+    pub fn fit(window: (u32, u32), fullscreen: bool) -> Self {
+        let scale = (window.0 / SCREEN_SIZE.0)
+            .min(window.1 / SCREEN_SIZE.1)
+            .max(1);
+        let offset = (
+            window.0.saturating_sub(SCREEN_SIZE.0 * scale) / 2,
+            window.1.saturating_sub(SCREEN_SIZE.1 * scale) / 2,
+        );
+        Viewport {
+            window,
+            scale,
+            offset,
+            fullscreen,
+        }
+    }
+    // The whole window, expressed in virtual pixels.
+    pub fn screen_coordinates(&self) -> Rect {
+        let scale = self.scale as f32;
+        Rect::new(
+            -(self.offset.0 as f32) / scale,
+            -(self.offset.1 as f32) / scale,
+            self.window.0 as f32 / scale,
+            self.window.1 as f32 / scale,
+        )
+    }
+    // Bars covering the window outside of the virtual screen, in virtual pixels.
+    pub fn letterbox(&self) -> Vec<Rect> {
+        let screen = self.screen_coordinates();
+        let (width, height) = (SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32);
+        vec![
+            Rect::new(screen.x, screen.y, -screen.x, screen.h),
+            Rect::new(width, screen.y, screen.w + screen.x - width, screen.h),
+            Rect::new(0.0, screen.y, width, -screen.y),
+            Rect::new(0.0, height, width, screen.h + screen.y - height),
+        ].into_iter()
+            .filter(|bar| bar.w > 0.0 && bar.h > 0.0)
+            .collect()
+    }
+    pub fn apply(&self, ctx: &mut Context) -> GameResult<()> {
+        set_screen_coordinates(ctx, self.screen_coordinates())
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport::fit(SCREEN_SIZE, false)
+    }
+}
+
+pub fn render_letterbox(ctx: &mut Context, viewport: &Viewport) -> GameResult<()> {
+    set_color(ctx, get_background_color(ctx))?;
+    for bar in viewport.letterbox() {
+        rectangle(ctx, DrawMode::Fill, bar)?;
+    }
+    Ok(())
+}
@@ -1,7 +1,6 @@
 use ggez::*;
 use specs::*;
 use ggez::graphics::*;
-use ggez::graphics::spritebatch::*;
 use state::*;
 use render::*;
 
@@ -31,11 +30,20 @@ const LOST_REGION: (f32, f32, f32, f32) = (
     LOOTED_REGION.2, LOOTED_REGION.3,
 );
 
-pub fn render_spread(spritebatch: &mut SpriteBatch, spirits: &Vec<SpiritType>, region: (f32, f32, f32, f32)) -> GameResult<()> {
+const CAPTURE_REGION: (f32, f32, f32, f32) = (
+    8.0, 40.0,
+    SCREEN_SIZE.0 as f32 - 16.0, SCREEN_SIZE.1 as f32 - 128.0,
+);
+const CAPTURE_INFO: (i32, i32, i32, i32) = (
+    0, SCREEN_SIZE.1 as i32 - 80,
+    SCREEN_SIZE.0 as i32, 80,
+);
+
+pub fn render_spread(list: &mut DrawList, atlas: &Atlas, spirits: &Vec<SpiritType>, region: (f32, f32, f32, f32)) -> GameResult<()> {
     let mut x = region.0;
     let mut y = region.1;
     for spirit in spirits.iter() {
-        spritebatch.add(spirit_sprite(&spirit,
+        list.sprite(spirit_sprite(atlas, &spirit,
             x as i32,
             y as i32,
             SPRITE_SIZE.0,
@@ -51,34 +59,90 @@ pub fn render_spread(spritebatch: &mut SpriteBatch, spirits: &Vec<SpiritType>, r
     Ok(())
 }
 
-pub fn render_looting(ctx: &mut Context, world: &mut World, captured: &Vec<SpiritType>, lost: &Vec<SpiritType>) -> GameResult<()> {
+pub fn render_looting(list: &mut DrawList, world: &mut World, captured: &Vec<SpiritType>, lost: &Vec<SpiritType>) -> GameResult<()> {
     type SystemData<'a> = (
         Entities<'a>,
-        WriteExpect<'a, SpriteBatch>,
+        ReadExpect<'a, Atlas>,
     );
-    let font = Font::default_font()?;
-    world.exec(|(entities, mut spritebatch): SystemData| -> GameResult<()> {
-        set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
-        let text = Text::new(
-            ctx,
-            &"Captured Spirits",
-            &font,
-        )?;
-        draw(ctx, &text, Point2::new(
+    world.exec(|(entities, atlas): SystemData| -> GameResult<()> {
+        list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+        list.text(&"Captured Spirits", Point2::new(
             LOOTED_HEADER.0,
             LOOTED_HEADER.1,
-        ), 0.0)?;
-        render_spread(&mut spritebatch, captured, LOOTED_REGION)?;
-        let text = Text::new(
-            ctx,
-            &"Lost Spirits",
-            &font,
-        )?;
-        draw(ctx, &text, Point2::new(
+        ))?;
+        render_spread(list, &atlas, captured, LOOTED_REGION)?;
+        list.text(&"Lost Spirits", Point2::new(
             LOST_HEADER.0,
             LOST_HEADER.1,
-        ), 0.0)?;
-        render_spread(&mut spritebatch, lost, LOST_REGION)?;
+        ))?;
+        render_spread(list, &atlas, lost, LOST_REGION)?;
+        Ok(())
+    })
+}
+
+fn capture_color(choice: &CaptureChoice) -> Color {
+    match choice {
+        CaptureChoice::Leave => [0.4, 0.4, 0.4, 1.0].into(),
+        CaptureChoice::Attempt => [1.0, 1.0, 1.0, 1.0].into(),
+        CaptureChoice::Charm => [1.0, 0.8, 0.0, 1.0].into(),
+    }
+}
+
+pub fn render_capturing(
+    list: &mut DrawList,
+    world: &mut World,
+    candidates: &Vec<SpiritType>,
+    choices: &Vec<CaptureChoice>,
+    index: usize,
+) -> GameResult<()> {
+    type SystemData<'a> = (
+        ReadStorage<'a, Bag>,
+        ReadExpect<'a, Atlas>,
+    );
+    world.exec(|(bags, atlas): SystemData| -> GameResult<()> {
+        list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+        list.text(&"Choose which spirits to capture", Point2::new(
+            LOOTED_HEADER.0,
+            LOOTED_HEADER.1,
+        ))?;
+        render_spread(list, &atlas, candidates, CAPTURE_REGION)?;
+        let mut x = CAPTURE_REGION.0;
+        let mut y = CAPTURE_REGION.1;
+        for (idx, choice) in choices.iter().enumerate() {
+            if idx == index {
+                list.set_color([0.0, 0.0, 1.0, 1.0].into())?;
+                list.rectangle(DrawMode::Line(4.0), Rect::new(
+                    x - 6.0, y - 6.0,
+                    (SPRITE_SIZE.0 + 12) as f32, (SPRITE_SIZE.1 + 12) as f32,
+                ))?;
+            }
+            list.set_color(capture_color(choice))?;
+            list.rectangle(DrawMode::Line(2.0), Rect::new(
+                x - 2.0, y - 2.0,
+                (SPRITE_SIZE.0 + 4) as f32, (SPRITE_SIZE.1 + 4) as f32,
+            ))?;
+            x += (SPRITE_SIZE.0 + 16) as f32;
+            if x + (SPRITE_SIZE.0 + 16) as f32 > CAPTURE_REGION.0 + CAPTURE_REGION.2 {
+                y += (SPRITE_SIZE.1 + 16) as f32;
+                x = CAPTURE_REGION.0;
+            }
+        }
+        let mut charms = 0;
+        for bag in (&bags).join() {
+            charms = bag.count(&ItemType::CaptureCharm);
+        }
+        text_outline(list, CAPTURE_INFO)?;
+        if let (Some(element), Some(choice)) = (candidates.get(index), choices.get(index)) {
+            text_in_box(list, &format!("{}\n{} x{}\nSpace: change choice. Backspace: finish.",
+                capture_choice_text(element, choice),
+                item_name(&ItemType::CaptureCharm),
+                charms,
+            ), (
+                CAPTURE_INFO.0 + 8,
+                CAPTURE_INFO.1 + 8,
+                CAPTURE_INFO.2 - 16,
+            ))?;
+        }
         Ok(())
     })
 }
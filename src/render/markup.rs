@@ -0,0 +1,192 @@
+use state::*;
+use render::*;
+
+// Text may color parts of itself with tags, as in "deals [damage]12[/] damage".
+// Unknown tags are left in the text as they are. "[[" is a literal "[", which escape_markup
+// uses to keep names typed by the player from being read as tags.
+const TAGS: [(&str, [f32; 4]); 9] = [
+    ("fire", [1.0, 0.5, 0.2, 1.0]),
+    ("water", [0.3, 0.6, 1.0, 1.0]),
+    ("slime", [0.4, 0.9, 0.3, 1.0]),
+    ("light", [1.0, 1.0, 0.6, 1.0]),
+    ("dark", [0.7, 0.4, 0.9, 1.0]),
+    ("damage", [1.0, 0.3, 0.3, 1.0]),
+    ("heal", [0.3, 1.0, 0.4, 1.0]),
+    ("defense", [0.4, 0.6, 1.0, 1.0]),
+    ("effective", [1.0, 0.8, 0.0, 1.0]),
+];
+const CLOSE_TAG: &str = "[/]";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub color: [f32; 4],
+}
+
+pub fn tagged(tag: &str, text: &str) -> String {
+    format!("[{}]{}{}", tag, text, CLOSE_TAG)
+}
+
+pub fn escape_markup(text: &str) -> String {
+    text.replace('[', "[[")
+}
+
+pub fn element_tag(element: &SpiritType) -> &'static str {
+    match element {
+        SpiritType::Fire(_) => "fire",
+        SpiritType::Water(_) => "water",
+        SpiritType::Slime(_) => "slime",
+        SpiritType::Light(_) => "light",
+        SpiritType::Dark(_) => "dark",
+    }
+}
+
+fn tag_color(tag: &str) -> Option<[f32; 4]> {
+    TAGS.iter()
+        .find(|(name, _color)| *name == tag)
+        .map(|(_name, color)| *color)
+}
+
+// Splits marked up text into runs of one color each. Tags do not nest.
+pub fn parse_markup(text: &str, color: [f32; 4]) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut current = Span { text: String::new(), color };
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let (before, tag_start) = rest.split_at(start);
+        current.text.push_str(before);
+        if tag_start.starts_with("[[") {
+            current.text.push('[');
+            rest = &tag_start[2..];
+            continue;
+        }
+        let tag = tag_start.find(']').map(|end| &tag_start[..end + 1]);
+        let next_color = match tag {
+            Some(CLOSE_TAG) => Some(color),
+            Some(tag) => tag_color(&tag[1..tag.len() - 1]),
+            None => None,
+        };
+        match (tag, next_color) {
+            (Some(tag), Some(next_color)) => {
+                if !current.text.is_empty() {
+                    spans.push(current);
+                }
+                current = Span { text: String::new(), color: next_color };
+                rest = &tag_start[tag.len()..];
+            },
+            _ => {
+                current.text.push('[');
+                rest = &tag_start[1..];
+            },
+        }
+    }
+    current.text.push_str(rest);
+    if !current.text.is_empty() {
+        spans.push(current);
+    }
+    spans
+}
+
+// The text as it reads on screen, without its tags.
+pub fn plain_text(text: &str) -> String {
+    parse_markup(text, [1.0, 1.0, 1.0, 1.0]).into_iter()
+        .map(|span| span.text)
+        .collect()
+}
+
+fn push_span(line: &mut Vec<Span>, text: &str, color: [f32; 4]) {
+    let merged = match line.last_mut() {
+        Some(ref mut last) if last.color == color => {
+            last.text.push_str(text);
+            true
+        },
+        _ => false,
+    };
+    if !merged {
+        line.push(Span { text: text.to_string(), color });
+    }
+}
+
+fn line_width(line: &Vec<Span>) -> i32 {
+    line.iter().map(|span| text_width(&span.text)).sum()
+}
+
+// Breaks the spans into lines no wider than width at spaces and explicit line breaks.
+// Words longer than a whole line are split wherever they run out of room.
+pub fn wrap_spans(spans: &Vec<Span>, width: i32) -> Vec<Vec<Span>> {
+    let mut words: Vec<Vec<Span>> = vec![Vec::new()];
+    let mut breaks = Vec::new();
+    for span in spans.iter() {
+        for c in span.text.chars() {
+            match c {
+                '\n' => {
+                    breaks.push(words.len());
+                    words.push(Vec::new());
+                },
+                ' ' => words.push(Vec::new()),
+                _ => {
+                    let mut buffer = [0; 4];
+                    push_span(words.last_mut().unwrap(), c.encode_utf8(&mut buffer), span.color);
+                },
+            }
+        }
+    }
+    let mut lines = vec![Vec::new()];
+    for (index, word) in words.into_iter().enumerate() {
+        if breaks.contains(&index) {
+            lines.push(Vec::new());
+        }
+        let line_is_empty = lines.last().map_or(true, |line| line.is_empty());
+        let space = match line_is_empty {
+            true => 0,
+            false => text_width(" "),
+        };
+        if !line_is_empty && line_width(lines.last().unwrap()) + space + line_width(&word) > width {
+            lines.push(Vec::new());
+        } else if !line_is_empty {
+            let color = lines.last().unwrap().last().unwrap().color;
+            push_span(lines.last_mut().unwrap(), " ", color);
+        }
+        for span in word.iter() {
+            for c in span.text.chars() {
+                let mut buffer = [0; 4];
+                let c = c.encode_utf8(&mut buffer);
+                let full = {
+                    let line = lines.last().unwrap();
+                    !line.is_empty() && line_width(line) + text_width(c) > width
+                };
+                if full {
+                    lines.push(Vec::new());
+                }
+                push_span(lines.last_mut().unwrap(), c, span.color);
+            }
+        }
+    }
+    lines
+}
+
+// Keeps only the first count visible characters, for text that is still being typed out.
+pub fn reveal_lines(lines: &Vec<Vec<Span>>, count: usize) -> Vec<Vec<Span>> {
+    let mut remaining = count;
+    let mut revealed = Vec::new();
+    for line in lines.iter() {
+        let mut shown = Vec::new();
+        for span in line.iter() {
+            let length = span.text.chars().count();
+            if remaining >= length {
+                shown.push(span.clone());
+                remaining -= length;
+            } else {
+                if remaining > 0 {
+                    shown.push(Span {
+                        text: span.text.chars().take(remaining).collect(),
+                        color: span.color,
+                    });
+                }
+                remaining = 0;
+            }
+        }
+        revealed.push(shown);
+    }
+    revealed
+}
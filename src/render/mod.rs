@@ -5,60 +5,124 @@ mod inventory;
 mod text;
 mod looting;
 mod sprites;
+mod items;
+mod settings;
+mod fusion;
+mod details;
+mod bestiary;
+mod scores;
+mod draw;
+mod layout;
+mod atlas;
+mod tiles;
+mod font;
+mod markup;
+#[cfg(test)]
+mod tests;
 
 use state::*;
 use ggez::*;
 use specs::*;
 use ggez::graphics::*;
 pub use self::text::*;
+pub use self::draw::*;
+pub use self::layout::*;
+pub use self::atlas::*;
+pub use self::font::*;
+pub use self::markup::*;
 use self::world::*;
 use self::debug::*;
 use self::combat::*;
 use self::inventory::*;
 use self::looting::*;
 use self::sprites::*;
+use self::tiles::*;
+use self::items::*;
+use self::settings::*;
+use self::fusion::*;
+use self::details::*;
+use self::bestiary::*;
+use self::scores::*;
 
 const START_BUTTON: (i32, i32, i32, i32) = (
     SCREEN_SIZE.0 as i32 / 2 - 64, SCREEN_SIZE.1 as i32 / 2 - 128,
     128, 64,
 );
+const SETTINGS_BUTTON: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 + 96, SCREEN_SIZE.1 as i32 / 2 - 128,
+    128, 64,
+);
+const BESTIARY_BUTTON: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 - 224, SCREEN_SIZE.1 as i32 / 2 - 128,
+    128, 64,
+);
+const LEADERBOARD_BUTTON: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 - 64, SCREEN_SIZE.1 as i32 / 2 - 56,
+    128, 40,
+);
 const INFO_BUTTON: (i32, i32, i32, i32) = (
     SCREEN_SIZE.0 as i32 / 2 - 64, SCREEN_SIZE.1 as i32 / 2 + 64,
     128, 64,
 );
-const GAME_OVER: (i32, i32, i32, i32) = (
-    SCREEN_SIZE.0 as i32 / 2 - 32, SCREEN_SIZE.1 as i32 / 2 - 16,
-    128, 32,
-);
 const INFO_AREA: (i32, i32, i32, i32) = (
     0, SCREEN_SIZE.1 as i32 / 2,
     SCREEN_SIZE.0 as i32, SCREEN_SIZE.1 as i32 / 2,
 );
 
-pub fn render_world(ctx: &mut Context, world: &mut World) -> GameResult<()> {
+pub fn render_world(list: &mut DrawList, world: &mut World) -> GameResult<()> {
     let play_state = world.read_resource::<PlayState>().clone();
     // println!("{:?}", play_state);
     match play_state {
         PlayState::InWorld => {
-            render_in_world(ctx, world)
+            render_in_world(list, world)
         },
         PlayState::InBattle => {
             let battle_state = world.read_resource::<BattleState>().clone();
             if battle_state.retreating {
-                render_inventory(ctx, world, true)
+                render_inventory(list, world, true)
             } else {
-                render_combat(ctx, world)
+                render_combat(list, world)
             }
         },
         PlayState::Combining => {
-            render_inventory(ctx, world, false)
+            render_inventory(list, world, false)
         },
         PlayState::Looting { captured, lost } => {
-            render_looting(ctx, world, &captured, &lost)
+            render_looting(list, world, &captured, &lost)
+        },
+        PlayState::Capturing { candidates, choices, index } => {
+            render_capturing(list, world, &candidates, &choices, index)
+        },
+        PlayState::Inspecting(entity) => {
+            render_inspect(list, world, entity)
+        },
+        PlayState::Items { index, .. } => {
+            render_items(list, world, index)
+        },
+        PlayState::Settings(index) => {
+            render_settings(list, world, index)
+        },
+        PlayState::Fusing(index) => {
+            render_fusing(list, world, index)
+        },
+        PlayState::Details(index) => {
+            render_details(list, world, index, false)
+        },
+        PlayState::Naming(index) => {
+            render_details(list, world, index, true)
+        },
+        PlayState::Bestiary(index) => {
+            render_bestiary(list, world, index)
+        },
+        PlayState::Leaderboard(daily) => {
+            render_leaderboard(list, world, daily)
+        },
+        PlayState::NewGame(index) => {
+            render_new_game(list, index)
         },
         PlayState::MainMenu(val) => {
             text_outline_color(
-                ctx,
+                list,
                 START_BUTTON,
                 match val {
                     0 => [0.0, 1.0, 1.0, 1.0].into(),
@@ -66,39 +130,68 @@ pub fn render_world(ctx: &mut Context, world: &mut World) -> GameResult<()> {
                 },
             )?;
             text_in_box(
-                ctx,
+                list,
                 &format!("Enter Semb"),
                 (START_BUTTON.0 + 8, START_BUTTON.1 + 8, START_BUTTON.2 - 16),
             )?;
             text_outline_color(
-                ctx,
+                list,
+                SETTINGS_BUTTON,
+                match val {
+                    1 => [0.0, 1.0, 1.0, 1.0].into(),
+                    _ => [1.0, 1.0, 1.0, 1.0].into(),
+                },
+            )?;
+            text_in_box(
+                list,
+                &format!("Settings"),
+                (SETTINGS_BUTTON.0 + 8, SETTINGS_BUTTON.1 + 8, SETTINGS_BUTTON.2 - 16),
+            )?;
+            text_outline_color(
+                list,
+                BESTIARY_BUTTON,
+                match val {
+                    3 => [0.0, 1.0, 1.0, 1.0].into(),
+                    _ => [1.0, 1.0, 1.0, 1.0].into(),
+                },
+            )?;
+            text_in_box(
+                list,
+                &format!("Bestiary"),
+                (BESTIARY_BUTTON.0 + 8, BESTIARY_BUTTON.1 + 8, BESTIARY_BUTTON.2 - 16),
+            )?;
+            text_outline_color(
+                list,
+                LEADERBOARD_BUTTON,
+                match val {
+                    2 => [0.0, 1.0, 1.0, 1.0].into(),
+                    _ => [1.0, 1.0, 1.0, 1.0].into(),
+                },
+            )?;
+            text_in_box(
+                list,
+                &format!("High Scores"),
+                (LEADERBOARD_BUTTON.0 + 8, LEADERBOARD_BUTTON.1 + 8, LEADERBOARD_BUTTON.2 - 16),
+            )?;
+            text_outline_color(
+                list,
                 INFO_AREA,
                 [0.0, 1.0, 1.0, 1.0].into(),
             )?;
             text_in_box(
-                ctx,
+                list,
                 &format!("{}\n{}\n{}\n{}\n{}",
                 "Use arrow keys or WASD to navigate the world and menus.",
                 "Press Space to select menu options.",
-                "Press Backspace to enter and exit your inventory.",
+                "Press Backspace to enter and exit your inventory, I to open your bag, Tab in battle to browse the enemy lineup, and L in battle to read the battle log.",
                 "Battle spirits in an endless dungeon. Collect enough to create stronger spirits.",
-                "Select a spirit in your inventory to combine it with others"),
+                "Select a spirit in your inventory to combine it with others. L locks, R releases, O sorts, E shows details."),
                 (INFO_AREA.0 + 8, INFO_AREA.1 + 8, INFO_AREA.2 - 16),
             );
             Ok(())
         },
         PlayState::GameOver => {
-            text_outline_color(
-                ctx,
-                GAME_OVER,
-                [1.0, 0.0, 0.0, 1.0].into(),
-            )?;
-            text_in_box(
-                ctx,
-                &format!("Game Over"),
-                (GAME_OVER.0 + 8, GAME_OVER.1 + 8, GAME_OVER.2 - 16),
-            )?;
-            Ok(())
+            render_game_over(list, world)
         },
         _ => {
             Ok(())
@@ -0,0 +1,101 @@
+use ggez::*;
+use specs::*;
+use ggez::graphics::*;
+use state::*;
+use render::*;
+
+const SUMMARY_HEADER: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 - 64, 16,
+    128, 32,
+);
+const SUMMARY_AREA: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 - 160, 64,
+    320, 256,
+);
+
+pub fn run_summary_text(run_stats: &RunStats) -> String {
+    format!("Difficulty: {}\nDeepest floor: {}\nBattles won: {}\nSpirits captured: {}\nSpirits lost: {}\nFusions performed: {}\nDamage dealt: {}\nTurns taken: {}\n\nScore: {}",
+        difficulty_name(&run_stats.difficulty),
+        run_stats.depth + 1,
+        run_stats.battles_won,
+        run_stats.spirits_captured,
+        run_stats.spirits_lost,
+        run_stats.fusions,
+        run_stats.damage_dealt,
+        run_stats.turns,
+        run_stats.calculate_score(),
+    )
+}
+
+pub fn render_game_over(list: &mut DrawList, world: &mut World) -> GameResult<()> {
+    let run_stats = world.read_resource::<RunStats>();
+    text_outline_color(
+        list,
+        SUMMARY_HEADER,
+        [1.0, 0.0, 0.0, 1.0].into(),
+    )?;
+    text_in_box(
+        list,
+        &format!("Game Over"),
+        (SUMMARY_HEADER.0 + 8, SUMMARY_HEADER.1 + 8, SUMMARY_HEADER.2 - 16),
+    )?;
+    text_outline(list, SUMMARY_AREA)?;
+    text_in_box(
+        list,
+        &format!("{}\n\nPress any key to continue.", run_summary_text(&run_stats)),
+        (SUMMARY_AREA.0 + 8, SUMMARY_AREA.1 + 8, SUMMARY_AREA.2 - 16),
+    )?;
+    Ok(())
+}
+
+const LEADERBOARD_AREA: (i32, i32, i32, i32) = (
+    8, 8,
+    SCREEN_SIZE.0 as i32 - 16, SCREEN_SIZE.1 as i32 - 16,
+);
+const LEADERBOARD_LINE_HEIGHT: f32 = 24.0;
+
+fn party_text(party: &Vec<SpiritType>) -> String {
+    let mut best: Vec<&SpiritType> = party.iter().collect();
+    best.sort_by(|a, b| b.level().cmp(&a.level()));
+    let names: Vec<String> = best.iter().take(3).map(|element| spirit_name(element)).collect();
+    match party.len() > 3 {
+        true => format!("{} +{}", names.join(", "), party.len() - 3),
+        false => names.join(", "),
+    }
+}
+
+pub fn render_leaderboard(list: &mut DrawList, world: &mut World, daily: bool) -> GameResult<()> {
+    let high_scores = world.read_resource::<HighScores>();
+    text_outline(list, LEADERBOARD_AREA)?;
+    let header = match daily {
+        true => format!("Daily runs for {}", date_text(today())),
+        false => format!("Random runs"),
+    };
+    list.set_color([0.0, 1.0, 1.0, 1.0].into())?;
+    list.text(&format!("{} (Left/Right to switch)", header), Point2::new(
+        LEADERBOARD_AREA.0 as f32 + 8.0,
+        LEADERBOARD_AREA.1 as f32 + 8.0,
+    ))?;
+    list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+    let board = high_scores.board(daily);
+    if board.len() == 0 {
+        list.text(&"No runs recorded yet", Point2::new(
+            LEADERBOARD_AREA.0 as f32 + 8.0,
+            LEADERBOARD_AREA.1 as f32 + 8.0 + LEADERBOARD_LINE_HEIGHT,
+        ))?;
+    }
+    for (idx, run) in board.iter().enumerate() {
+        list.text(&format!("{:2}. {:6} Floor {:3} {} #{:x} {}",
+            idx + 1,
+            run.score,
+            run.depth + 1,
+            date_text(run.day),
+            run.seed & 0xffff,
+            party_text(&run.party),
+        ), Point2::new(
+            LEADERBOARD_AREA.0 as f32 + 8.0,
+            LEADERBOARD_AREA.1 as f32 + 8.0 + LEADERBOARD_LINE_HEIGHT * (idx + 1) as f32,
+        ))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use ggez::*;
+use specs::*;
+use ggez::graphics::*;
+use state::*;
+use render::*;
+
+const SETTINGS_AREA: (i32, i32, i32, i32) = (
+    0, 0,
+    SCREEN_SIZE.0 as i32, SCREEN_SIZE.1 as i32,
+);
+const SETTING_LINE_HEIGHT: i32 = 24;
+const LABEL_OFFSET: (f32, f32) = (16.0, 16.0);
+const VALUE_OFFSET: f32 = SCREEN_SIZE.0 as f32 / 2.0;
+
+pub fn render_settings(list: &mut DrawList, world: &mut World, index: usize) -> GameResult<()> {
+    let settings = world.read_resource::<Settings>();
+    text_outline(list, SETTINGS_AREA)?;
+    for (idx, (label, value)) in settings.options().iter().enumerate() {
+        if idx == index {
+            list.set_color([0.0, 1.0, 1.0, 1.0].into())?;
+        } else {
+            list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+        }
+        let y = SETTINGS_AREA.1 as f32 + LABEL_OFFSET.1 + (SETTING_LINE_HEIGHT * idx as i32) as f32;
+        list.text(label, Point2::new(SETTINGS_AREA.0 as f32 + LABEL_OFFSET.0, y))?;
+        list.text(&format!("< {} >", value), Point2::new(SETTINGS_AREA.0 as f32 + VALUE_OFFSET, y))?;
+    }
+    Ok(())
+}
+
+const DIFFICULTY_AREA: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 - 160, 32,
+    320, 120,
+);
+const DIFFICULTY_DESCRIPTION: (i32, i32, i32, i32) = (
+    SCREEN_SIZE.0 as i32 / 2 - 160, 168,
+    320, 96,
+);
+
+pub fn render_new_game(list: &mut DrawList, index: usize) -> GameResult<()> {
+    text_outline(list, DIFFICULTY_AREA)?;
+    for (idx, difficulty) in DIFFICULTIES.iter().enumerate() {
+        if idx == index {
+            list.set_color([0.0, 1.0, 1.0, 1.0].into())?;
+        } else {
+            list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+        }
+        let y = DIFFICULTY_AREA.1 as f32 + LABEL_OFFSET.1 + (SETTING_LINE_HEIGHT * (idx as i32 + 1)) as f32;
+        list.text(&difficulty_name(difficulty), Point2::new(DIFFICULTY_AREA.0 as f32 + LABEL_OFFSET.0, y))?;
+    }
+    list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+    list.text(&"Choose a difficulty", Point2::new(
+        DIFFICULTY_AREA.0 as f32 + LABEL_OFFSET.0,
+        DIFFICULTY_AREA.1 as f32 + LABEL_OFFSET.1,
+    ))?;
+    text_outline(list, DIFFICULTY_DESCRIPTION)?;
+    if let Some(difficulty) = DIFFICULTIES.get(index) {
+        text_in_box(list, &difficulty_text(difficulty), (
+            DIFFICULTY_DESCRIPTION.0 + 8,
+            DIFFICULTY_DESCRIPTION.1 + 8,
+            DIFFICULTY_DESCRIPTION.2 - 16,
+        ))?;
+    }
+    Ok(())
+}
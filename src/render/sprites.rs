@@ -2,94 +2,151 @@ use ggez::*;
 use specs::*;
 use ggez::graphics::*;
 use state::*;
+use render::*;
+use std::f32::consts::PI;
 
-const INVENTORY_SPRITE_SIZE: (f32, f32) = (32.0, 32.0);
-const SPRITESHEET_SIZE: (f32, f32) = (512.0, 512.0);
-
-fn sprite_src(pu: f32, pv: f32, pw: f32, ph: f32) -> Rect {
-    Rect::new(pu / SPRITESHEET_SIZE.0, pv / SPRITESHEET_SIZE.1, pw / SPRITESHEET_SIZE.0, ph / SPRITESHEET_SIZE.1)
-}
-
-fn sprite_scale(pw: f32, ph: f32, width: i32, height: i32) -> Point2 {
-    Point2::new(width as f32 / pw, height as f32 / ph)
-}
-
-pub fn battle_spirit_background(
+fn atlas_param(
+    atlas: &Atlas,
+    rect: (f32, f32, f32, f32),
     x: i32, y: i32, width: i32, height: i32,
     color: Option<Color>,
 ) -> DrawParam {
+    let (pu, pv, pw, ph) = rect;
     DrawParam {
-        src: sprite_src(96.0, 0.0, 32.0, 32.0),
+        src: Rect::new(pu / atlas.size.0, pv / atlas.size.1, pw / atlas.size.0, ph / atlas.size.1),
         dest: Point2::new(x as f32, y as f32),
         rotation: 0.0,
-        scale: sprite_scale(32.0, 32.0, width, height),
+        scale: Point2::new(width as f32 / pw, height as f32 / ph),
         offset: Point2::new(0.0, 0.0),
         shear: Point2::new(0.0, 0.0),
-        color: None,
+        color,
     }
 }
 
+pub fn battle_spirit_background(
+    atlas: &Atlas,
+    x: i32, y: i32, width: i32, height: i32,
+    color: Option<Color>,
+) -> DrawParam {
+    atlas_param(atlas, atlas.rect("spirit_background"), x, y, width, height, color)
+}
+
 pub fn spirit_sprite(
+    atlas: &Atlas,
     element: &SpiritType,
     x: i32, y: i32, width: i32, height: i32,
     color: Option<Color>,
 ) -> DrawParam {
-    let (pu, pv) = match element {
-        SpiritType::Fire(0) => (0.0, 32.0),
-        SpiritType::Fire(1) => (32.0, 32.0),
-        SpiritType::Fire(2) => (64.0, 32.0),
-        SpiritType::Water(0) => (0.0, 64.0),
-        SpiritType::Water(1) => (32.0, 64.0),
-        SpiritType::Water(2) => (64.0, 64.0),
-        SpiritType::Slime(0) => (0.0, 96.0),
-        SpiritType::Slime(1) => (32.0, 96.0),
-        SpiritType::Slime(2) => (64.0, 96.0),
-        SpiritType::Dark(0) => (0.0, 128.0),
-        SpiritType::Dark(1) => (32.0, 128.0),
-        SpiritType::Dark(2) => (64.0, 128.0),
-        SpiritType::Light(0) => (0.0, 160.0),
-        SpiritType::Light(1) => (32.0, 160.0),
-        SpiritType::Light(2) => (64.0, 160.0),
-        _ => (128.0, 0.0),
+    let name = match tier(element) {
+        Some(tier) => tier.sprite,
+        None => "unknown",
     };
-    let src = sprite_src(pu, pv, INVENTORY_SPRITE_SIZE.0, INVENTORY_SPRITE_SIZE.1);
-    let dest = Point2::new(x as f32, y as f32);
-    let rotation = 0.0;
-    let scale = Point2::new(
-        width as f32 / INVENTORY_SPRITE_SIZE.0,
-        height as f32 / INVENTORY_SPRITE_SIZE.1
-    );
-    DrawParam {
-        src,
-        dest,
-        rotation,
-        scale,
-        offset: Point2::new(0.0, 0.0),
-        shear: Point2::new(0.0, 0.0),
-        color,
+    let color = match (color, tier(element)) {
+        (Some(color), _) => Some(color),
+        (None, Some(tier)) => tier.tint.map(|tint| tint.into()),
+        (None, None) => None,
+    };
+    atlas_param(atlas, atlas.rect(name), x, y, width, height, color)
+}
+
+pub fn stair_sprite(atlas: &Atlas, x: i32, y: i32, width: i32, height: i32) -> DrawParam {
+    atlas_param(atlas, atlas.rect("stair"), x, y, width, height, None)
+}
+
+pub fn floor_sprite(atlas: &Atlas, variant: u32, x: i32, y: i32, size: i32, color: Color) -> DrawParam {
+    atlas_param(atlas, atlas.rect(&format!("floor_{}", variant)), x, y, size, size, Some(color))
+}
+
+pub fn wall_sprite(atlas: &Atlas, mask: u32, x: i32, y: i32, size: i32, color: Color) -> DrawParam {
+    atlas_param(atlas, atlas.rect(&format!("wall_{}", mask & 15)), x, y, size, size, Some(color))
+}
+
+// The corners from the upper bits of a wall mask, drawn over the wall sprite.
+pub fn wall_corner_sprites(atlas: &Atlas, mask: u32, x: i32, y: i32, size: i32, color: Color) -> Vec<DrawParam> {
+    [(16, "ne"), (32, "se"), (64, "sw"), (128, "nw")].iter()
+        .filter(|&&(bit, _)| mask & bit != 0)
+        .map(|&(_, corner)| {
+            atlas_param(atlas, atlas.rect(&format!("wall_corner_{}", corner)), x, y, size, size, Some(color))
+        })
+        .collect()
+}
+
+pub fn enemy_bar_sprite(atlas: &Atlas, x: i32, y: i32, width: i32, height: i32) -> DrawParam {
+    atlas_param(atlas, atlas.rect("enemy_bar"), x, y, width, height, None)
+}
+
+pub fn ally_bar_sprite(atlas: &Atlas, x: i32, y: i32, width: i32, height: i32) -> DrawParam {
+    atlas_param(atlas, atlas.rect("ally_bar"), x, y, width, height, None)
+}
+
+fn attack_animation(element: &SpiritType) -> &'static str {
+    match element {
+        SpiritType::Fire(_) => "fire_attack",
+        SpiritType::Water(_) => "water_attack",
+        SpiritType::Slime(_) => "slime_attack",
+        SpiritType::Light(_) => "light_attack",
+        SpiritType::Dark(_) => "dark_attack",
     }
 }
 
-pub fn enemy_bar_sprite(x: i32, y: i32, width: i32, height: i32) -> DrawParam {
+// Moves a sprite by the current animation frame. Forward is +1 for enemies, who face down the screen,
+// and -1 for allies, who face up it.
+pub fn animated(param: DrawParam, frame: &AnimationFrame, forward: i32) -> DrawParam {
+    let color = match (param.color, frame.tint) {
+        (Some(color), Some(tint)) => Some(Color::new(
+            color.r * tint[0], color.g * tint[1], color.b * tint[2], color.a * tint[3],
+        )),
+        (None, Some(tint)) => Some(tint.into()),
+        (color, None) => color,
+    };
     DrawParam {
-        src: sprite_src(0.0, 0.0, 64.0, 16.0),
-        dest: Point2::new(x as f32, y as f32),
-        rotation: 0.0,
-        scale: sprite_scale(64.0, 16.0, width, height),
-        offset: Point2::new(0.0, 0.0),
-        shear: Point2::new(0.0, 0.0),
-        color: None,
+        dest: Point2::new(
+            param.dest.x + frame.offset.0 as f32,
+            param.dest.y + (frame.offset.1 * forward) as f32,
+        ),
+        color,
+        ..param
     }
 }
 
-pub fn ally_bar_sprite(x: i32, y: i32, width: i32, height: i32) -> DrawParam {
+// Each element's attack has its own path between the attacker and the target.
+pub fn attack_effect_sprite(
+    atlas: &Atlas,
+    effect: &AttackEffect,
+    from: (i32, i32),
+    to: (i32, i32),
+    size: i32,
+) -> DrawParam {
+    let progress = effect.progress();
+    let travel = |t: f32| (
+        from.0 as f32 + (to.0 - from.0) as f32 * t,
+        from.1 as f32 + (to.1 - from.1) as f32 * t,
+    );
+    let (position, scale, alpha) = match effect.element {
+        SpiritType::Fire(_) => (travel(progress), 0.5 + progress * 0.5, 1.0),
+        SpiritType::Water(_) => {
+            let (x, y) = travel(progress);
+            ((x + (progress * PI * 2.0).sin() * size as f32 / 2.0, y), 0.75, 0.8)
+        },
+        SpiritType::Slime(_) => {
+            let (x, y) = travel(progress);
+            ((x, y - (progress * PI * 3.0).sin().abs() * size as f32 / 2.0), 0.75, 1.0)
+        },
+        SpiritType::Light(_) => (travel(1.0), 0.5 + (progress * PI * 4.0).sin().abs() * 0.75, 1.0 - progress * 0.5),
+        SpiritType::Dark(_) => (travel(1.0), progress * 1.25, 1.0 - progress),
+    };
+    let width = size as f32 * scale;
+    let param = atlas_param(
+        atlas,
+        atlas.frame(attack_animation(&effect.element), progress),
+        0, 0, width as i32, width as i32,
+        Some([1.0, 1.0, 1.0, alpha].into()),
+    );
     DrawParam {
-        src: sprite_src(0.0, 16.0, 64.0, 16.0),
-        dest: Point2::new(x as f32, y as f32),
-        rotation: 0.0,
-        scale: sprite_scale(64.0, 16.0, width, height),
-        offset: Point2::new(0.0, 0.0),
-        shear: Point2::new(0.0, 0.0),
-        color: None,
+        dest: Point2::new(
+            position.0 + (size as f32 - width) / 2.0,
+            position.1 + (size as f32 - width) / 2.0,
+        ),
+        ..param
     }
 }
@@ -0,0 +1,129 @@
+use specs::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+use state::*;
+use render::*;
+use super::combat::render_combat;
+use super::inventory::render_inventory;
+
+// Every sprite falls back to "unknown" except the battle background, so sprite lines only
+// change when a layout does.
+fn test_atlas() -> Atlas {
+    let mut sprites = HashMap::new();
+    sprites.insert("unknown".to_string(), (0.0, 0.0, 32.0, 32.0));
+    sprites.insert("spirit_background".to_string(), (32.0, 0.0, 32.0, 32.0));
+    Atlas {
+        size: (64.0, 64.0),
+        sprites,
+        animations: HashMap::new(),
+    }
+}
+
+fn test_spirit(element: SpiritType, is_player: bool) -> Spirit {
+    let mut spirit = Spirit::new(element, is_player, &mut StdRng::from_seed([7; 32]));
+    spirit.moves = [
+        Move { name: "First".to_string(), effect: MoveType::DamageOne(4) },
+        Move { name: "Second".to_string(), effect: MoveType::DamageMany(2) },
+        Move { name: "Third".to_string(), effect: MoveType::Heal(3) },
+        Move { name: "Fourth".to_string(), effect: MoveType::Defend(1) },
+    ];
+    spirit
+}
+
+fn test_world() -> World {
+    let mut world = World::new();
+    world.register::<Spirit>();
+    world.register::<PlayerSpirit>();
+    world.register::<Player>();
+    world.register::<Animation>();
+    world.register::<Bag>();
+    world.add_resource(BattleState::new());
+    world.add_resource(BattleLog::default());
+    world.add_resource(FloatingNumbers::default());
+    world.add_resource(InventoryState::new());
+    world.add_resource(Settings::default());
+    world.add_resource(Difficulty::default());
+    world.add_resource(test_atlas());
+    world
+}
+
+fn snapshot(list: &DrawList) -> String {
+    let mut recorder = RecordingBackend::new();
+    recorder.render(list).unwrap();
+    recorder.snapshot()
+}
+
+fn assert_lines(snapshot: &str, expected: &[&str]) {
+    for line in expected.iter() {
+        assert!(
+            snapshot.lines().any(|drawn| drawn == *line),
+            "missing from the snapshot:\n{}\n\nsnapshot:\n{}", line, snapshot,
+        );
+    }
+}
+
+#[test]
+fn combat_layout() {
+    let mut world = test_world();
+    world.create_entity()
+        .with(test_spirit(SpiritType::Fire(0), false))
+        .build();
+    let ally = world.create_entity()
+        .with(test_spirit(SpiritType::Water(0), true))
+        .with(PlayerSpirit { active: true })
+        .build();
+    {
+        let mut battle_state = world.write_resource::<BattleState>();
+        battle_state.active_entity = Some(ally);
+        battle_state.combat_move = Some(0);
+    }
+    let mut list = DrawList::new();
+    render_combat(&mut list, &world).unwrap();
+    assert_lines(&snapshot(&list), &[
+        // The enemy in the first of ENEMY_LOCATIONS, highlighted as the target of the selected move.
+        "Rectangle { fill: false, line_width: 2.0, rect: [250.0, 6.0, 68.0, 68.0], color: [1.0, 0.8, 0.0, 1.0] }",
+        "Sprite { src: [0.5, 0.0, 0.5, 0.5], dest: [252.0, 8.0], scale: [2.0, 2.0], color: None }",
+        "Sprite { src: [0.0, 0.0, 0.5, 0.5], dest: [252.0, 8.0], scale: [2.0, 2.0], color: None }",
+        // The active ally.
+        "Sprite { src: [0.0, 0.0, 0.5, 0.5], dest: [284.0, 240.0], scale: [2.0, 2.0], color: None }",
+        // The moves, one in each of MOVE_AREAS.
+        "Text { text: \"First\", position: [384.0, 312.0], color: [0.0, 0.0, 1.0, 1.0] }",
+        "Text { text: \"Second\", position: [508.0, 312.0], color: [1.0, 1.0, 1.0, 1.0] }",
+        "Text { text: \"Third\", position: [384.0, 340.0], color: [1.0, 1.0, 1.0, 1.0] }",
+        "Text { text: \"Fourth\", position: [508.0, 340.0], color: [1.0, 1.0, 1.0, 1.0] }",
+    ]);
+}
+
+#[test]
+fn inventory_layout() {
+    let mut world = test_world();
+    world.create_entity()
+        .with(Player::new(vec![test_spirit(SpiritType::Fire(0), true)]))
+        .build();
+    let mut list = DrawList::new();
+    render_inventory(&mut list, &mut world, false).unwrap();
+    let name = format!(
+        "Text {{ text: {:?}, position: [376.0, 16.0], color: [1.0, 1.0, 1.0, 1.0] }}",
+        spirit_name(&SpiritType::Fire(0)),
+    );
+    assert_lines(&snapshot(&list), &[
+        // The selected slot, the one next to it and the first slot of the second row.
+        "Rectangle { fill: false, line_width: 4.0, rect: [8.0, 8.0, 64.0, 64.0], color: [0.0, 0.0, 1.0, 1.0] }",
+        "Rectangle { fill: false, line_width: 4.0, rect: [80.0, 8.0, 64.0, 64.0], color: [1.0, 1.0, 1.0, 1.0] }",
+        "Rectangle { fill: false, line_width: 4.0, rect: [8.0, 80.0, 64.0, 64.0], color: [1.0, 1.0, 1.0, 1.0] }",
+        "Sprite { src: [0.0, 0.0, 0.5, 0.5], dest: [8.0, 8.0], scale: [2.0, 2.0], color: None }",
+        name.as_str(),
+    ]);
+}
+
+#[test]
+fn nickname_tags_stay_literal() {
+    let mut spirit = test_spirit(SpiritType::Fire(0), true);
+    spirit.nickname = Some("[water]Bob[/]".to_string());
+    let spans = parse_markup(&marked_name(&spirit), [1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(spans, vec![Span {
+        text: format!("[water]Bob[/] the {}", spirit_name(&SpiritType::Fire(0))),
+        color: [1.0, 0.5, 0.2, 1.0],
+    }]);
+}
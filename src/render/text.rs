@@ -2,31 +2,37 @@ use state::*;
 use ggez::*;
 use ggez::graphics::*;
 use input::*;
+use render::*;
 
 pub fn spirit_name(element: &SpiritType) -> String {
-    match element {
-        SpiritType::Fire(0) => "Ember",
-        SpiritType::Fire(1) => "Fire Elemental",
-        SpiritType::Fire(2) => "Grand Efreet",
-
-        SpiritType::Water(0) => "Nymph",
-        SpiritType::Water(1) => "Water Maiden",
-        SpiritType::Water(2) => "Leviathan",
+    match tier(element) {
+        Some(tier) => tier.name,
+        None => "Unknown".to_string(),
+    }
+}
 
-        SpiritType::Slime(0) => "Lesser Slime",
-        SpiritType::Slime(1) => "Slime",
-        SpiritType::Slime(2) => "Greater Slime",
+pub fn display_name(spirit: &Spirit) -> String {
+    match spirit.nickname {
+        Some(ref nickname) => format!("{} the {}", nickname, spirit_name(&spirit.element)),
+        None => spirit_name(&spirit.element),
+    }
+}
 
-        SpiritType::Dark(0) => "Imp",
-        SpiritType::Dark(1) => "Fiend",
-        SpiritType::Dark(2) => "War sDominator",
+// Names colored by element, for text drawn with text_in_box.
+pub fn marked_name(spirit: &Spirit) -> String {
+    tagged(element_tag(&spirit.element), &escape_markup(&display_name(spirit)))
+}
 
-        SpiritType::Light(0) => "Wisp",
-        SpiritType::Light(1) => "Guardian",
-        SpiritType::Light(2) => "Holy Angel",
+pub fn marked_spirit_name(element: &SpiritType) -> String {
+    tagged(element_tag(element), &spirit_name(element))
+}
 
-        _ => "Unknown",
-    }.to_string()
+fn effectiveness_text(spirit: &Spirit, target: &Spirit) -> String {
+    match spirit.element.type_advantage(&target.element) {
+        advantage if advantage > 0 => format!(" {}", tagged("effective", "Super effective!")),
+        advantage if advantage < 0 => " Not very effective...".to_string(),
+        _ => String::new(),
+    }
 }
 
 pub fn spirit_level_text(element: &SpiritType) -> String {
@@ -39,33 +45,33 @@ pub fn spirit_level_text(element: &SpiritType) -> String {
     }
 }
 
-pub fn collide_text(element: &SpiritType) -> String {
+pub fn collide_text(element: &SpiritType, difficulty: &Difficulty) -> String {
     if !can_upgrade(element) {
         format!("Cannot exceed its current power")
     } else {
         match element {
             SpiritType::Fire(level) => format!("Can combust with {} {} to become a {}",
-                required_spirits(element),
+                required_spirits(element, difficulty),
                 spirit_name(element),
                 spirit_name(&SpiritType::Fire(level + 1)),
             ),
             SpiritType::Water(level) => format!("Can mingle with {} {} to become a {}",
-                required_spirits(element),
+                required_spirits(element, difficulty),
                 spirit_name(element),
                 spirit_name(&SpiritType::Water(level + 1)),
             ),
             SpiritType::Slime(level) => format!("Can absorb {} {} to become a {}",
-                required_spirits(element),
+                required_spirits(element, difficulty),
                 spirit_name(element),
                 spirit_name(&SpiritType::Slime(level + 1)),
             ),
             SpiritType::Light(level) => format!("Can accept the sacrifies of {} {} to become a {}",
-                required_spirits(element),
+                required_spirits(element, difficulty),
                 spirit_name(element),
                 spirit_name(&SpiritType::Light(level + 1)),
             ),
             SpiritType::Dark(level) => format!("Can consume {} {} to become a {}",
-                required_spirits(element),
+                required_spirits(element, difficulty),
                 spirit_name(element),
                 spirit_name(&SpiritType::Dark(level + 1)),
             ),
@@ -83,34 +89,196 @@ pub fn move_text(combat_move: &Move) -> String {
 }
 
 pub fn damage_one_text(combat_move: &Move, spirit: &Spirit, target: &Spirit, amount: u32, is_enemy: bool) -> String {
-    format!("{} uses {}!\nIt deals {} damage to {} {}!",
-        spirit_name(&spirit.element),
+    format!("{} uses {}!{}\nIt deals {} damage to {} {}!",
+        marked_name(spirit),
         combat_move.name,
-        amount,
+        effectiveness_text(spirit, target),
+        tagged("damage", &amount.to_string()),
         match is_enemy {
             true => "your",
             false => "the enemy",
         },
-        spirit_name(&target.element),
+        marked_name(target),
     )
 }
 
 pub fn heal_text(combat_move: &Move, spirit: &Spirit, amount: u32) -> String {
     format!("{} uses {}!\nIt heals {}!",
-        spirit_name(&spirit.element),
+        marked_name(spirit),
         combat_move.name,
-        amount,
+        tagged("heal", &amount.to_string()),
     )
 }
 
 pub fn defense_text(combat_move: &Move, spirit: &Spirit, amount: u32) -> String {
     format!("{} uses {}!\nIt raises its defense by {}!",
-        spirit_name(&spirit.element),
+        marked_name(spirit),
         combat_move.name,
-        amount,
+        tagged("defense", &amount.to_string()),
+    )
+}
+
+pub fn faint_text(spirit: &Spirit, is_enemy: bool) -> String {
+    format!("{} {} fainted!",
+        match is_enemy {
+            true => "The enemy",
+            false => "Your",
+        },
+        marked_name(spirit),
     )
 }
 
+pub fn encounter_text(count: usize) -> String {
+    match count {
+        1 => "A wild spirit appears!".to_string(),
+        _ => format!("A pack of {} spirits appears!", count),
+    }
+}
+
+pub fn auto_capture_text(captured: &Vec<SpiritType>, lost: &Vec<SpiritType>) -> String {
+    let names = |elements: &Vec<SpiritType>| elements.iter()
+        .map(|element| marked_spirit_name(element))
+        .collect::<Vec<String>>()
+        .join(", ");
+    match (captured.len(), lost.len()) {
+        (0, 0) => "You won the battle!".to_string(),
+        (_, 0) => format!("You won and captured {}!", names(captured)),
+        (0, _) => format!("You won, but your party had no room for {}.", names(lost)),
+        _ => format!("You won and captured {}!\nYour party had no room for {}.", names(captured), names(lost)),
+    }
+}
+
+pub fn difficulty_name(difficulty: &Difficulty) -> String {
+    match difficulty {
+        Difficulty::Casual => "Casual",
+        Difficulty::Normal => "Normal",
+        Difficulty::Hard => "Hard",
+    }.to_string()
+}
+
+pub fn difficulty_text(difficulty: &Difficulty) -> String {
+    match difficulty {
+        Difficulty::Casual => "Fainted spirits recover when you reach the stairs.",
+        Difficulty::Normal => "Fainted spirits stay fainted until revived.",
+        Difficulty::Hard => "Fainted spirits are lost forever. Enemies are stronger and fusions need more spirits.",
+    }.to_string()
+}
+
+pub fn origin_text(origin_depth: Option<u32>) -> String {
+    match origin_depth {
+        Some(depth) => format!("Captured on floor {}", depth + 1),
+        None => "Joined you at the start of your journey".to_string(),
+    }
+}
+
+pub fn danger_text(danger: u32) -> String {
+    match danger {
+        0 => "They look frightened of you",
+        1 => "They look weaker than you",
+        2 => "They look like a fair fight",
+        _ => "They look very dangerous",
+    }.to_string()
+}
+
+pub fn roster_text(enemy: &Spirit, position: usize, count: usize) -> String {
+    let status = match enemy.health {
+        0 => "Fainted".to_string(),
+        _ => format!("Health {}, Defense {}", health(enemy), enemy.defense),
+    };
+    format!(
+        "Enemy {} of {}: {} (Level {}). {}.",
+        position + 1, count, spirit_name(&enemy.element), enemy.element.level(), status,
+    )
+}
+
+pub fn pack_text(spirits: &Vec<Spirit>) -> String {
+    let mut counts: Vec<(SpiritType, u32)> = Vec::new();
+    for spirit in spirits.iter() {
+        let mut found = false;
+        for entry in counts.iter_mut() {
+            if entry.0 == spirit.element {
+                entry.1 += 1;
+                found = true;
+            }
+        }
+        if !found {
+            counts.push((spirit.element.clone(), 1));
+        }
+    }
+    counts.iter()
+        .map(|(element, count)| format!("{} x {} (Level {})", count, spirit_name(element), element.level()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn item_name(item: &ItemType) -> String {
+    match item {
+        ItemType::Potion => "Potion",
+        ItemType::Elixir => "Elixir",
+        ItemType::DefenseTonic => "Defense Tonic",
+        ItemType::CaptureCharm => "Capture Charm",
+        ItemType::Revive => "Revive",
+        ItemType::Catalyst => "Fusion Catalyst",
+        ItemType::Key => "Key",
+    }.to_string()
+}
+
+pub fn item_text(item: &ItemType) -> String {
+    match item {
+        ItemType::Potion => format!("Heals a spirit for {}", POTION_HEAL),
+        ItemType::Elixir => format!("Fully heals a spirit"),
+        ItemType::DefenseTonic => format!("Raises defense by {} in battle", TONIC_DEFENSE),
+        ItemType::CaptureCharm => format!("Tries to capture an enemy. Works best on weakened enemies"),
+        ItemType::Revive => format!("Revives a fainted spirit"),
+        ItemType::Catalyst => format!("Stands in for one spirit when combining"),
+        ItemType::Key => format!("Opens a locked door"),
+    }
+}
+
+pub fn item_used_text(item: &ItemType, target: Option<&SpiritType>, success: bool) -> String {
+    match (item, target, success) {
+        (ItemType::CaptureCharm, Some(target), true) => format!("You use a {}!\nThe {} was captured!",
+            item_name(item),
+            marked_spirit_name(target),
+        ),
+        (ItemType::CaptureCharm, Some(target), false) => format!("You use a {}!\nThe {} broke free!",
+            item_name(item),
+            marked_spirit_name(target),
+        ),
+        (_, Some(target), true) => format!("You use a {} on {}!",
+            item_name(item),
+            marked_spirit_name(target),
+        ),
+        _ => format!("You can't use a {} now.",
+            item_name(item),
+        ),
+    }
+}
+
+pub fn capture_choice_text(element: &SpiritType, choice: &CaptureChoice) -> String {
+    match choice {
+        CaptureChoice::Leave => format!("Leave the {} behind", spirit_name(element)),
+        CaptureChoice::Attempt => format!("Try to capture the {} ({}% chance)",
+            spirit_name(element),
+            (defeated_capture_chance(element) * 100.0) as u32,
+        ),
+        CaptureChoice::Charm => format!("Use a {} on the {}",
+            item_name(&ItemType::CaptureCharm),
+            spirit_name(element),
+        ),
+    }
+}
+
+pub fn sort_text(sort_mode: &SortMode) -> String {
+    match sort_mode {
+        SortMode::Unsorted => "unsorted",
+        SortMode::Element => "by element",
+        SortMode::Level => "by level",
+        SortMode::Health => "by health",
+        SortMode::Ratings => "by ratings",
+    }.to_string()
+}
+
 pub fn health(spirit: &Spirit) -> String {
     format!("{} / {}", spirit.health, spirit.max_health)
 }
@@ -160,16 +328,16 @@ pub fn iv_text(spirit: &Spirit) -> String {
     format!("Ratings: x({})h({})d({})", attack_rating, stamina_rating, defense_rating)
 }
 
-pub fn text_outline(ctx: &mut Context, region: (i32, i32, i32, i32)) -> GameResult<()> {
-    set_color(ctx, [0.0, 0.0, 0.0, 1.0].into())?;
-    rectangle(ctx, DrawMode::Fill, Rect::new_i32(
+pub fn text_outline(list: &mut DrawList, region: (i32, i32, i32, i32)) -> GameResult<()> {
+    list.set_color([0.0, 0.0, 0.0, 1.0].into())?;
+    list.rectangle(DrawMode::Fill, Rect::new_i32(
         region.0,
         region.1,
         region.2,
         region.3,
     ))?;
-    set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
-    rectangle(ctx, DrawMode::Line(2.0), Rect::new_i32(
+    list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+    list.rectangle(DrawMode::Line(2.0), Rect::new_i32(
         region.0 + 4,
         region.1 + 4,
         region.2 - 8,
@@ -179,19 +347,19 @@ pub fn text_outline(ctx: &mut Context, region: (i32, i32, i32, i32)) -> GameResu
 }
 
 pub fn text_outline_color(
-    ctx: &mut Context,
+    list: &mut DrawList,
     region: (i32, i32, i32, i32),
     line_color: Color
 ) -> GameResult<()> {
-    set_color(ctx, [0.0, 0.0, 0.0, 1.0].into())?;
-    rectangle(ctx, DrawMode::Fill, Rect::new_i32(
+    list.set_color([0.0, 0.0, 0.0, 1.0].into())?;
+    list.rectangle(DrawMode::Fill, Rect::new_i32(
         region.0,
         region.1,
         region.2,
         region.3,
     ))?;
-    set_color(ctx, line_color)?;
-    rectangle(ctx, DrawMode::Line(2.0), Rect::new_i32(
+    list.set_color(line_color)?;
+    list.rectangle(DrawMode::Line(2.0), Rect::new_i32(
         region.0 + 4,
         region.1 + 4,
         region.2 - 8,
@@ -200,19 +368,38 @@ pub fn text_outline_color(
     Ok(())
 }
 
-pub fn text_in_box(ctx: &mut Context, text: &String, region: (i32, i32, i32)) -> GameResult<()> {
-    let font = Font::default_font()?;
-    let (size, lines) = font.get_wrap(text, region.2 as usize);
+// Draws wrapped lines one under the other, each span in its own color.
+pub fn render_lines(list: &mut DrawList, lines: &Vec<Vec<Span>>, position: (i32, i32)) -> GameResult<()> {
+    let color = list.color();
     for (idx, line) in lines.iter().enumerate() {
-        let text = Text::new(
-            ctx,
-            &line,
-            &font,
-        )?;
-        draw(ctx, &text, Point2::new(
-            region.0 as f32,
-            region.1 as f32 + idx as f32 * 16.0,
-        ), 0.0)?;
+        let mut x = position.0;
+        for span in line.iter() {
+            list.set_color(span.color.into())?;
+            list.text(&span.text, Point2::new(
+                x as f32,
+                (position.1 + idx as i32 * GLYPH_SIZE.1) as f32,
+            ))?;
+            x += text_width(&span.text);
+        }
     }
-    Ok(())
+    list.set_color(color.into())
+}
+
+pub fn text_in_box(list: &mut DrawList, text: &String, region: (i32, i32, i32)) -> GameResult<()> {
+    text_in_box_revealed(list, text, region, None)
+}
+
+// Only the first reveal characters are drawn while the text is being typed out.
+pub fn text_in_box_revealed(
+    list: &mut DrawList,
+    text: &str,
+    region: (i32, i32, i32),
+    reveal: Option<usize>,
+) -> GameResult<()> {
+    let lines = wrap_spans(&parse_markup(text, list.color()), region.2);
+    let lines = match reveal {
+        Some(count) => reveal_lines(&lines, count),
+        None => lines,
+    };
+    render_lines(list, &lines, (region.0, region.1))
 }
@@ -0,0 +1,60 @@
+use state::*;
+
+pub struct TileTheme {
+    pub floor: [f32; 4],
+    pub wall: [f32; 4],
+}
+
+const FLOORS_PER_THEME: u32 = 5;
+
+// Stone, moss, sunken ruins, embers and shadow.
+const THEMES: [TileTheme; 5] = [
+    TileTheme { floor: [1.0, 1.0, 1.0, 1.0], wall: [0.9, 0.9, 1.0, 1.0] },
+    TileTheme { floor: [0.8, 0.95, 0.75, 1.0], wall: [0.55, 0.75, 0.5, 1.0] },
+    TileTheme { floor: [0.75, 0.85, 1.0, 1.0], wall: [0.45, 0.6, 0.85, 1.0] },
+    TileTheme { floor: [1.0, 0.8, 0.65, 1.0], wall: [0.85, 0.4, 0.3, 1.0] },
+    TileTheme { floor: [0.8, 0.7, 0.9, 1.0], wall: [0.45, 0.35, 0.6, 1.0] },
+];
+
+// Themes change every few floors and start over once the deepest one has been reached.
+pub fn tile_theme(depth: u32) -> &'static TileTheme {
+    &THEMES[(depth / FLOORS_PER_THEME) as usize % THEMES.len()]
+}
+
+fn neighbor(level: &Level, loc: (u32, u32), offset: (i32, i32)) -> bool {
+    let x = loc.0 as i64 + offset.0 as i64;
+    let y = loc.1 as i64 + offset.1 as i64;
+    x >= 0 && y >= 0 && level.has_tile((x as u32, y as u32))
+}
+
+// Picks the wall sprite from the floors around it: 1 for a floor to the north, 2 east, 4 south
+// and 8 west. A floor diagonally past two walls adds a corner: 16 north east, 32 south east,
+// 64 south west and 128 north west. Walls with no floor next to them are left dark.
+pub fn wall_mask(level: &Level, loc: (u32, u32)) -> Option<u32> {
+    let mut mask = 0;
+    for (bit, offset) in [(1, (0, -1)), (2, (1, 0)), (4, (0, 1)), (8, (-1, 0))].iter() {
+        if neighbor(level, loc, *offset) {
+            mask |= bit;
+        }
+    }
+    // Each corner with the two sides it sits between.
+    for (bit, offset, sides) in [
+        (16, (1, -1), 1 | 2),
+        (32, (1, 1), 2 | 4),
+        (64, (-1, 1), 4 | 8),
+        (128, (-1, -1), 8 | 1),
+    ].iter() {
+        if mask & sides == 0 && neighbor(level, loc, *offset) {
+            mask |= bit;
+        }
+    }
+    match mask {
+        0 => None,
+        _ => Some(mask),
+    }
+}
+
+// Floors mix their variants by position, so the same floor always looks the same.
+pub fn floor_variant(loc: (u32, u32)) -> u32 {
+    (loc.0.wrapping_mul(7) ^ loc.1.wrapping_mul(13)) % 4
+}
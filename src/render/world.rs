@@ -1,26 +1,75 @@
 use ggez::*;
 use specs::*;
 use ggez::graphics::*;
-use ggez::graphics::spritebatch::*;
 use state::*;
 use render::*;
 
 const OFFSET: u32 = (TILE_SIZE - CHAR_SIZE) / 2;
 
-pub fn render_in_world(ctx: &mut Context, world: &World) -> GameResult<()> {
+const INSPECT_HEADER: (f32, f32) = (
+    8.0, 8.0,
+);
+const INSPECT_AREA: (i32, i32, i32, i32) = (
+    0, 32,
+    SCREEN_SIZE.0 as i32, 128,
+);
+const INSPECT_REGION: (f32, f32, f32, f32) = (
+    8.0, 168.0,
+    SCREEN_SIZE.0 as f32 - 16.0, SCREEN_SIZE.1 as f32 - 176.0,
+);
+
+fn badge_size(pack_size: usize) -> i32 {
+    match pack_size {
+        1 => 12,
+        2..=4 => 16,
+        5..=8 => 20,
+        _ => 24,
+    }
+}
+
+fn item_color(item: &ItemType) -> Color {
+    match item {
+        ItemType::Potion => [1.0, 0.2, 0.2, 1.0].into(),
+        ItemType::Elixir => [1.0, 0.4, 0.8, 1.0].into(),
+        ItemType::DefenseTonic => [0.3, 0.6, 1.0, 1.0].into(),
+        ItemType::CaptureCharm => [0.3, 1.0, 0.5, 1.0].into(),
+        ItemType::Revive => [1.0, 0.9, 0.3, 1.0].into(),
+        ItemType::Catalyst => [0.7, 0.3, 1.0, 1.0].into(),
+        ItemType::Key => [0.9, 0.7, 0.0, 1.0].into(),
+    }
+}
+
+fn danger_color(danger: u32) -> Color {
+    match danger {
+        0 => [0.0, 1.0, 0.0, 1.0].into(),
+        1 => [1.0, 1.0, 0.0, 1.0].into(),
+        2 => [1.0, 0.5, 0.0, 1.0].into(),
+        _ => [1.0, 0.0, 0.0, 1.0].into(),
+    }
+}
+
+pub fn render_in_world(list: &mut DrawList, world: &World) -> GameResult<()> {
     let camera = world.read_resource::<Camera>();
     let level = world.read_resource::<Level>();
-    let mut spritebatch = world.write_resource::<SpriteBatch>();
-    set_color(ctx, [1.0, 1.0, 1.0, 1.0].into())?;
+    let atlas = world.read_resource::<Atlas>();
+    let theme = tile_theme(level.depth);
     for x in 0..camera.width{
         for y in 0..camera.height {
-            if level.has_tile((camera.x_offset + x, camera.y_offset + y)) {
-                rectangle(ctx, DrawMode::Fill, Rect::new_i32(
-                    (x * TILE_SIZE) as i32,
-                    (y * TILE_SIZE) as i32,
-                    TILE_SIZE as i32,
-                    TILE_SIZE as i32,
-                ))?;
+            let loc = (camera.x_offset() + x, camera.y_offset() + y);
+            let (screen_x, screen_y) = camera.to_screen((loc.0 as f32, loc.1 as f32));
+            if level.has_tile(loc) {
+                list.sprite(floor_sprite(&atlas, floor_variant(loc),
+                    screen_x, screen_y, TILE_SIZE as i32, theme.floor.into(),
+                ));
+            } else if let Some(mask) = wall_mask(&level, loc) {
+                list.sprite(wall_sprite(&atlas, mask,
+                    screen_x, screen_y, TILE_SIZE as i32, theme.wall.into(),
+                ));
+                for corner in wall_corner_sprites(&atlas, mask,
+                    screen_x, screen_y, TILE_SIZE as i32, theme.wall.into(),
+                ) {
+                    list.sprite(corner);
+                }
             }
         }
     }
@@ -28,39 +77,42 @@ pub fn render_in_world(ctx: &mut Context, world: &World) -> GameResult<()> {
     let players = world.read_storage::<Player>();
     let stairs = world.read_storage::<Stair>();
     let encounters = world.read_storage::<Encounter>();
+    let pickups = world.read_storage::<Pickup>();
+    let doors = world.read_storage::<Door>();
+    let shrines = world.read_storage::<Shrine>();
+    let mut player_strength = 0;
+    for player in (&players).join() {
+        player_strength = party_strength(&player.spirits);
+    }
     for (encounter, position) in (&encounters, &positions).join() {
-        if position.location.0 >= camera.x_offset
-            && position.location.1 >= camera.y_offset
-            && position.location.0 < camera.x_offset + camera.width
-            && position.location.1 < camera.y_offset + camera.height {
-            let (pos_x, pos_y) = (
-                position.location.0 - camera.x_offset,
-                position.location.1 - camera.y_offset,
-            );
-            if let Some(head) = encounter.spirits.first() {
-                spritebatch.add(spirit_sprite(&head.element,
-                    (pos_x * TILE_SIZE + OFFSET) as i32,
-                    (pos_y * TILE_SIZE + OFFSET) as i32,
+        if camera.visible(position.location) {
+            let (screen_x, screen_y) = camera.to_screen(position.position());
+            if let Some(leader) = encounter.leader() {
+                list.sprite(spirit_sprite(&atlas, &leader.element,
+                    screen_x + OFFSET as i32,
+                    screen_y + OFFSET as i32,
                     (CHAR_SIZE) as i32,
                     (CHAR_SIZE) as i32,
                     None,
                 ));
+                let badge = badge_size(encounter.spirits.len());
+                list.sprite(battle_spirit_background(&atlas, 
+                    screen_x + (TILE_SIZE - OFFSET) as i32 - badge,
+                    screen_y + (TILE_SIZE - OFFSET) as i32 - badge,
+                    badge,
+                    badge,
+                    Some(danger_color(encounter.danger(player_strength))),
+                ));
             }
         }
     }
     for (player, position) in (&players, &positions).join() {
-        if position.location.0 >= camera.x_offset
-            && position.location.1 >= camera.y_offset
-            && position.location.0 < camera.x_offset + camera.width
-            && position.location.1 < camera.y_offset + camera.height {
-            let (pos_x, pos_y) = (
-                position.location.0 - camera.x_offset,
-                position.location.1 - camera.y_offset,
-            );
+        if camera.visible(position.location) {
+            let (screen_x, screen_y) = camera.to_screen(position.position());
             if let Some(head) = player.spirits.first() {
-                spritebatch.add(spirit_sprite(&head.element,
-                    (pos_x * TILE_SIZE + OFFSET) as i32,
-                    (pos_y * TILE_SIZE + OFFSET) as i32,
+                list.sprite(spirit_sprite(&atlas, &head.element,
+                    screen_x + OFFSET as i32,
+                    screen_y + OFFSET as i32,
                     (CHAR_SIZE) as i32,
                     (CHAR_SIZE) as i32,
                     None,
@@ -69,22 +121,93 @@ pub fn render_in_world(ctx: &mut Context, world: &World) -> GameResult<()> {
         }
     }
     for (_stair, position) in (&stairs, &positions).join() {
-        if position.location.0 >= camera.x_offset
-            && position.location.1 >= camera.y_offset
-            && position.location.0 < camera.x_offset + camera.width
-            && position.location.1 < camera.y_offset + camera.height {
-            let (pos_x, pos_y) = (
-                position.location.0 - camera.x_offset,
-                position.location.1 - camera.y_offset,
-            );
-            spritebatch.add(spirit_sprite(&SpiritType::Fire(4),
-                (pos_x * TILE_SIZE + OFFSET) as i32,
-                (pos_y * TILE_SIZE + OFFSET) as i32,
+        if camera.visible(position.location) {
+            let (screen_x, screen_y) = camera.to_screen(position.position());
+            list.sprite(stair_sprite(&atlas, 
+                screen_x + OFFSET as i32,
+                screen_y + OFFSET as i32,
                 (CHAR_SIZE) as i32,
                 (CHAR_SIZE) as i32,
-                None,
+            ));
+        }
+    }
+    for (_door, position) in (&doors, &positions).join() {
+        if camera.visible(position.location) {
+            let (screen_x, screen_y) = camera.to_screen(position.position());
+            list.sprite(battle_spirit_background(&atlas, 
+                screen_x + OFFSET as i32,
+                screen_y + OFFSET as i32,
+                (CHAR_SIZE) as i32,
+                (CHAR_SIZE) as i32,
+                Some([0.5, 0.3, 0.1, 1.0].into()),
+            ));
+        }
+    }
+    for (shrine, position) in (&shrines, &positions).join() {
+        if camera.visible(position.location) {
+            let (screen_x, screen_y) = camera.to_screen(position.position());
+            list.sprite(battle_spirit_background(&atlas, 
+                screen_x + OFFSET as i32,
+                screen_y + OFFSET as i32,
+                (CHAR_SIZE) as i32,
+                (CHAR_SIZE) as i32,
+                match shrine.used {
+                    true => Some([0.4, 0.4, 0.4, 1.0].into()),
+                    false => Some([0.3, 1.0, 0.9, 1.0].into()),
+                },
+            ));
+        }
+    }
+    for (pickup, position) in (&pickups, &positions).join() {
+        if camera.visible(position.location) {
+            let (screen_x, screen_y) = camera.to_screen(position.position());
+            list.sprite(battle_spirit_background(&atlas, 
+                screen_x + (TILE_SIZE / 4) as i32,
+                screen_y + (TILE_SIZE / 4) as i32,
+                (TILE_SIZE / 2) as i32,
+                (TILE_SIZE / 2) as i32,
+                Some(item_color(&pickup.item)),
             ));
         }
     }
     Ok(())
 }
+
+pub fn render_inspect(list: &mut DrawList, world: &mut World, entity: Entity) -> GameResult<()> {
+    type SystemData<'a> = (
+        ReadStorage<'a, Encounter>,
+        ReadStorage<'a, Player>,
+        ReadExpect<'a, Atlas>,
+    );
+    world.exec(|(encounters, players, atlas): SystemData| -> GameResult<()> {
+        let mut player_strength = 0;
+        for player in (&players).join() {
+            player_strength = party_strength(&player.spirits);
+        }
+        if let Some(encounter) = encounters.get(entity) {
+            list.set_color([1.0, 1.0, 1.0, 1.0].into())?;
+            list.text(&format!("A pack of {} spirits. {}.",
+                    encounter.spirits.len(),
+                    danger_text(encounter.danger(player_strength)),
+                ), Point2::new(
+                INSPECT_HEADER.0,
+                INSPECT_HEADER.1,
+            ))?;
+            text_outline_color(
+                list,
+                INSPECT_AREA,
+                danger_color(encounter.danger(player_strength)),
+            )?;
+            text_in_box(list, &pack_text(&encounter.spirits), (
+                INSPECT_AREA.0 + 8,
+                INSPECT_AREA.1 + 8,
+                INSPECT_AREA.2 - 16,
+            ))?;
+            let elements = encounter.spirits.iter()
+                .map(|spirit| spirit.element.clone())
+                .collect();
+            render_spread(list, &atlas, &elements, INSPECT_REGION)?;
+        }
+        Ok(())
+    })
+}
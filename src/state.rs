@@ -1,13 +1,16 @@
 use ggez::*;
 use ggez::audio::Source;
 use specs::*;
-use std::collections::HashMap;
-use input::Direction;
+use std::collections::{HashMap, VecDeque};
+use input::{Direction, DIRECTIONS};
 use rand::*;
 use rand::distributions::{Normal, Distribution};
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rand::rngs::StdRng;
 use std::cmp;
+pub use tiers::*;
 
+// The virtual resolution every screen is laid out in, scaled up to fit the window.
 pub const SCREEN_SIZE: (u32, u32) = (632, 368);
 pub const TILE_SIZE: u32 = 64;
 pub const CHAR_SIZE: u32 = 56;
@@ -21,6 +24,14 @@ pub enum MoveType {
 }
 
 impl MoveType {
+    // How many enemies from the front of the lineup the move hits.
+    pub fn enemy_targets(&self) -> usize {
+        match self {
+            MoveType::DamageMany(_) => 3,
+            MoveType::DamageOne(_) => 1,
+            MoveType::Heal(_) | MoveType::Defend(_) => 0,
+        }
+    }
     pub fn actual_amount(&self, attacker: &Spirit, defender: &Spirit) -> u32 {
         let mut rng = thread_rng();
         let attack = attacker.attack * (attacker.element.level() + 1);
@@ -338,7 +349,7 @@ impl CombatEffects {
                     }
                 },
                 CombatEffect::Defense(amount) => {
-                    if spirit.defense > 6 {
+                    if spirit.defense < 6 {
                         spirit.defense += 1;
                         if *amount > 1 {
                             new_effects.push(CombatEffect::Defense(amount - 1));
@@ -359,7 +370,205 @@ impl CombatEffects {
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationFrame {
+    // Offsets are in virtual pixels, with y pointing towards the other side of the battle.
+    pub offset: (i32, i32),
+    pub tint: Option<[f32; 4]>,
+    pub millis: u64,
+}
+
+const HIT_TINT: Option<[f32; 4]> = Some([1.0, 0.3, 0.3, 1.0]);
+
+const IDLE_FRAMES: [AnimationFrame; 4] = [
+    AnimationFrame { offset: (0, 0), tint: None, millis: 200 },
+    AnimationFrame { offset: (0, -1), tint: None, millis: 200 },
+    AnimationFrame { offset: (0, -2), tint: None, millis: 200 },
+    AnimationFrame { offset: (0, -1), tint: None, millis: 200 },
+];
+const LUNGE_FRAMES: [AnimationFrame; 6] = [
+    AnimationFrame { offset: (0, 4), tint: None, millis: 40 },
+    AnimationFrame { offset: (0, 8), tint: None, millis: 40 },
+    AnimationFrame { offset: (0, 12), tint: None, millis: 60 },
+    AnimationFrame { offset: (0, 8), tint: None, millis: 40 },
+    AnimationFrame { offset: (0, 4), tint: None, millis: 40 },
+    AnimationFrame { offset: (0, 0), tint: None, millis: 40 },
+];
+const HIT_FRAMES: [AnimationFrame; 6] = [
+    AnimationFrame { offset: (-2, 0), tint: HIT_TINT, millis: 60 },
+    AnimationFrame { offset: (2, 0), tint: None, millis: 60 },
+    AnimationFrame { offset: (-2, 0), tint: HIT_TINT, millis: 60 },
+    AnimationFrame { offset: (2, 0), tint: None, millis: 60 },
+    AnimationFrame { offset: (0, 0), tint: HIT_TINT, millis: 60 },
+    AnimationFrame { offset: (0, 0), tint: None, millis: 60 },
+];
+const FAINT_FRAMES: [AnimationFrame; 6] = [
+    AnimationFrame { offset: (0, 0), tint: Some([1.0, 1.0, 1.0, 0.85]), millis: 80 },
+    AnimationFrame { offset: (0, 0), tint: Some([1.0, 1.0, 1.0, 0.7]), millis: 80 },
+    AnimationFrame { offset: (0, 0), tint: Some([1.0, 1.0, 1.0, 0.55]), millis: 80 },
+    AnimationFrame { offset: (0, 0), tint: Some([1.0, 1.0, 1.0, 0.4]), millis: 80 },
+    AnimationFrame { offset: (0, 0), tint: Some([1.0, 1.0, 1.0, 0.25]), millis: 80 },
+    AnimationFrame { offset: (0, 0), tint: Some([1.0, 1.0, 1.0, 0.0]), millis: 80 },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationKind {
+    Idle,
+    Lunge,
+    Hit,
+    Faint,
+}
+
+impl AnimationKind {
+    pub fn frames(&self) -> &'static [AnimationFrame] {
+        match self {
+            AnimationKind::Idle => &IDLE_FRAMES,
+            AnimationKind::Lunge => &LUNGE_FRAMES,
+            AnimationKind::Hit => &HIT_FRAMES,
+            AnimationKind::Faint => &FAINT_FRAMES,
+        }
+    }
+    pub fn looping(&self) -> bool {
+        *self == AnimationKind::Idle
+    }
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Animation {
+    pub kind: AnimationKind,
+    pub frame: usize,
+    pub elapsed: Duration,
+    pub finished: bool,
+    pub last_health: u32,
+}
+
+impl Animation {
+    pub fn new(health: u32, phase: usize) -> Self {
+        Animation {
+            kind: AnimationKind::Idle,
+            frame: phase % IDLE_FRAMES.len(),
+            elapsed: Duration::new(0, 0),
+            finished: false,
+            last_health: health,
+        }
+    }
+    pub fn play(&mut self, kind: AnimationKind) {
+        self.kind = kind;
+        self.frame = 0;
+        self.elapsed = Duration::new(0, 0);
+        self.finished = false;
+    }
+    pub fn current(&self) -> &'static AnimationFrame {
+        let frames = self.kind.frames();
+        &frames[self.frame.min(frames.len() - 1)]
+    }
+    pub fn update(&mut self, delta: Duration) {
+        if self.finished {
+            return;
+        }
+        let frames = self.kind.frames();
+        self.elapsed += delta;
+        loop {
+            let length = Duration::from_millis(frames[self.frame].millis);
+            if self.elapsed < length {
+                break;
+            }
+            self.elapsed -= length;
+            if self.frame + 1 < frames.len() {
+                self.frame += 1;
+            } else if self.kind.looping() {
+                self.frame = 0;
+            } else {
+                self.finished = true;
+                break;
+            }
+        }
+    }
+    pub fn is_fading(&self) -> bool {
+        self.kind == AnimationKind::Faint && !self.finished
+    }
+}
+
+pub fn duration_millis(duration: Duration) -> f32 {
+    duration.as_secs() as f32 * 1000.0 + duration.subsec_nanos() as f32 / 1_000_000.0
+}
+
+pub const ATTACK_EFFECT_MILLIS: u64 = 450;
+
+// An element's attack travelling from the attacker to its targets on the battle screen.
+#[derive(Debug, Clone)]
+pub struct AttackEffect {
+    pub element: SpiritType,
+    pub attacker: Entity,
+    pub targets: Vec<Entity>,
+    pub elapsed: Duration,
+    pub started: bool,
+}
+
+impl AttackEffect {
+    pub fn new(element: SpiritType, attacker: Entity, targets: Vec<Entity>) -> Self {
+        AttackEffect {
+            element,
+            attacker,
+            targets,
+            elapsed: Duration::new(0, 0),
+            started: false,
+        }
+    }
+    pub fn progress(&self) -> f32 {
+        (duration_millis(self.elapsed) / ATTACK_EFFECT_MILLIS as f32).min(1.0)
+    }
+    pub fn finished(&self) -> bool {
+        self.elapsed >= Duration::from_millis(ATTACK_EFFECT_MILLIS)
+    }
+}
+
+pub const FLOATING_NUMBER_MILLIS: u64 = 900;
+
+// A number rising off a battle sprite when an effect lands on it. Several effects landing
+// together are stacked in rows so they don't overlap.
+#[derive(Debug, Clone)]
+pub struct FloatingNumber {
+    pub target: Entity,
+    pub effect: CombatEffect,
+    pub row: usize,
+    pub elapsed: Duration,
+}
+
+impl FloatingNumber {
+    pub fn progress(&self) -> f32 {
+        (duration_millis(self.elapsed) / FLOATING_NUMBER_MILLIS as f32).min(1.0)
+    }
+    pub fn finished(&self) -> bool {
+        self.elapsed >= Duration::from_millis(FLOATING_NUMBER_MILLIS)
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct FloatingNumbers {
+    pub numbers: Vec<FloatingNumber>,
+}
+
+impl FloatingNumbers {
+    pub fn spawn(&mut self, target: Entity, effects: &Vec<CombatEffect>) {
+        for (row, effect) in effects.iter().enumerate() {
+            self.numbers.push(FloatingNumber {
+                target,
+                effect: effect.clone(),
+                row,
+                elapsed: Duration::new(0, 0),
+            });
+        }
+    }
+    pub fn update(&mut self, delta: Duration) {
+        for number in self.numbers.iter_mut() {
+            number.elapsed += delta;
+        }
+        self.numbers.retain(|number| !number.finished());
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum SpiritType {
     Fire(u32),
     Water(u32),
@@ -378,6 +587,15 @@ impl SpiritType {
             SpiritType::Dark(level) => *level,
         }
     }
+    pub fn element_index(&self) -> u32 {
+        match self {
+            SpiritType::Fire(_) => 0,
+            SpiritType::Water(_) => 1,
+            SpiritType::Slime(_) => 2,
+            SpiritType::Light(_) => 3,
+            SpiritType::Dark(_) => 4,
+        }
+    }
     pub fn base_level(&self) -> SpiritType {
         match self {
             SpiritType::Fire(level) => SpiritType::Fire(0),
@@ -405,6 +623,29 @@ impl SpiritType {
     }
 }
 
+pub fn move_pool(element: &SpiritType, is_player: bool) -> [Move; 8] {
+    match (is_player, element) {
+        (false, _) => {
+            enemy_moves()
+        },
+        (true, SpiritType::Fire(_)) => {
+            fire_moves()
+        },
+        (true, SpiritType::Water(_)) => {
+            water_moves()
+        },
+        (true, SpiritType::Slime(_)) => {
+            slime_moves()
+        },
+        (true, SpiritType::Dark(_)) => {
+            dark_moves()
+        },
+        (true, SpiritType::Light(_)) => {
+            light_moves()
+        },
+    }
+}
+
 #[derive(Component, Debug, Clone)]
 pub struct Spirit {
     pub element: SpiritType,
@@ -415,95 +656,39 @@ pub struct Spirit {
     pub attack: u32,
     pub stamina: u32,
     pub moves: [Move; 4],
+    pub locked: bool,
+    pub nickname: Option<String>,
+    pub wins: u32,
+    pub damage_dealt: u32,
+    pub origin_depth: Option<u32>,
 }
-fn next_spirit(spirit: Spirit) -> Spirit {
-    match spirit.element {
-        SpiritType::Fire(level) => {
-            Spirit {
-                element: SpiritType::Fire(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Water(level) => {
-            Spirit {
-                element: SpiritType::Water(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Slime(level) => {
-            Spirit {
-                element: SpiritType::Slime(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Light(level) => {
-            Spirit {
-                element: SpiritType::Light(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
-        SpiritType::Dark(level) => {
-            Spirit {
-                element: SpiritType::Dark(level + 1),
-                max_health: spirit.max_health * 4,
-                health: spirit.max_health * 4,
-                moves: spirit.moves,
-                attack: spirit.attack,
-                base_defense: spirit.base_defense,
-                stamina: spirit.stamina,
-                defense: spirit.base_defense,
-            }
-        },
+pub fn next_spirit(spirit: Spirit) -> Spirit {
+    // Health quadruples through the named tiers. Past them it grows in step with the level,
+    // so a MAX_LEVEL spirit stays well inside a u32.
+    let level = spirit.element.level();
+    let max_health = match level + 1 < named_levels(&spirit.element) {
+        true => spirit.max_health * 4,
+        false => spirit.max_health + spirit.max_health / cmp::max(1, level),
+    };
+    let element = match spirit.element {
+        SpiritType::Fire(level) => SpiritType::Fire(level + 1),
+        SpiritType::Water(level) => SpiritType::Water(level + 1),
+        SpiritType::Slime(level) => SpiritType::Slime(level + 1),
+        SpiritType::Light(level) => SpiritType::Light(level + 1),
+        SpiritType::Dark(level) => SpiritType::Dark(level + 1),
+    };
+    Spirit {
+        element,
+        max_health,
+        health: max_health,
+        defense: spirit.base_defense,
+        ..spirit
     }
 }
 
 impl Spirit {
-    pub fn new(element: SpiritType, is_player: bool) -> Self {
-        let mut rng = thread_rng();
-        let mut moves = match (is_player, &element) {
-            (false, _) => {
-                enemy_moves()
-            },
-            (true, SpiritType::Fire(_)) => {
-                fire_moves()
-            },
-            (true, SpiritType::Water(_)) => {
-                water_moves()
-            },
-            (true, SpiritType::Slime(_)) => {
-                slime_moves()
-            },
-            (true, SpiritType::Dark(_)) => {
-                dark_moves()
-            },
-            (true, SpiritType::Light(_)) => {
-                light_moves()
-            },
-        };
+    pub fn new<R: Rng>(element: SpiritType, is_player: bool, rng: &mut R) -> Self {
+        let mut moves = move_pool(&element, is_player);
         let (max_health, base_defense, attack, stamina) = match is_player {
             true => {
                 (
@@ -537,6 +722,11 @@ impl Spirit {
                 moves[2].clone(),
                 moves[3].clone(),
             ],
+            locked: false,
+            nickname: None,
+            wins: 0,
+            damage_dealt: 0,
+            origin_depth: None,
         };
         if is_player {
             for _ in 0..(element.level()) {
@@ -545,9 +735,24 @@ impl Spirit {
         }
         spirit
     }
+    pub fn captured(element: SpiritType, depth: u32) -> Self {
+        let mut spirit = Spirit::new(element, true, &mut thread_rng());
+        spirit.origin_depth = Some(depth);
+        spirit
+    }
     pub fn level(&self) -> u32 {
         self.element.level()
     }
+    pub fn ratings(&self) -> u32 {
+        self.attack + self.stamina + self.base_defense as u32 * 3
+    }
+    pub fn strength(&self) -> u32 {
+        self.max_health + self.attack * (self.level() + 1) * 2
+    }
+}
+
+pub fn party_strength(spirits: &Vec<Spirit>) -> u32 {
+    spirits.iter().map(|spirit| spirit.strength()).sum()
 }
 
 #[derive(Component, Debug)]
@@ -558,6 +763,76 @@ pub struct PlayerSpirit {
 #[derive(Component, Debug, Clone)]
 pub struct Player {
     pub spirits: Vec<Spirit>,
+    pub fainted: Vec<Spirit>,
+    pub storage: Vec<Spirit>,
+}
+
+impl Player {
+    pub fn new(spirits: Vec<Spirit>) -> Self {
+        Player {
+            spirits,
+            fainted: Vec::new(),
+            storage: Vec::new(),
+        }
+    }
+    // Fainted spirits only rejoin while the party has room. The rest stay fainted.
+    pub fn rest(&mut self, revive: bool, party_size: usize) {
+        if revive {
+            let room = party_size.saturating_sub(self.spirits.len()).min(self.fainted.len());
+            let revived: Vec<Spirit> = self.fainted.drain(..room).collect();
+            self.spirits.extend(revived);
+        }
+        for spirit in self.spirits.iter_mut() {
+            spirit.health = spirit.max_health;
+            spirit.defense = spirit.base_defense;
+        }
+    }
+    // New spirits join the party while it has room and go to the storage box after that.
+    pub fn add_spirit(&mut self, spirit: Spirit, party_size: usize) {
+        if self.spirits.len() < party_size {
+            self.spirits.push(spirit);
+        } else {
+            self.storage.push(spirit);
+        }
+    }
+    pub fn deposit(&mut self, index: usize) -> bool {
+        if index < self.spirits.len() && self.spirits.len() > 1 {
+            let spirit = self.spirits.remove(index);
+            self.storage.push(spirit);
+            true
+        } else {
+            false
+        }
+    }
+    pub fn withdraw(&mut self, index: usize, party_size: usize) -> bool {
+        if index < self.storage.len() && self.spirits.len() < party_size {
+            let spirit = self.storage.remove(index);
+            self.spirits.push(spirit);
+            true
+        } else {
+            false
+        }
+    }
+    pub fn revive_one(&mut self, party_size: usize) -> bool {
+        if self.spirits.len() >= party_size {
+            return false;
+        }
+        match self.fainted.pop() {
+            Some(mut spirit) => {
+                spirit.health = spirit.max_health / 2;
+                self.spirits.push(spirit);
+                true
+            },
+            None => false,
+        }
+    }
+    // Revives as many fainted spirits as the party has room for. The rest stay fainted.
+    pub fn revive_all(&mut self, party_size: usize) {
+        let room = party_size.saturating_sub(self.spirits.len()).min(self.fainted.len());
+        for _ in 0..room {
+            self.revive_one(party_size);
+        }
+    }
 }
 
 #[derive(Default, Clone, Debug)]
@@ -571,6 +846,15 @@ pub struct BattleState {
     pub active_entity: Option<Entity>,
     pub encounter_entity: Option<Entity>,
     pub notification: Option<String>,
+    pub item_menu: Option<usize>,
+    pub use_item: Option<ItemType>,
+    pub roster: Option<usize>,
+    pub attack_effect: Option<AttackEffect>,
+    pub notification_age: Duration,
+    pub notification_typed: f32,
+    pub notification_revealed: bool,
+    // Every spirit that has been sent out this battle. Only they are credited with the win.
+    pub participants: Vec<Entity>,
 }
 
 impl BattleState {
@@ -585,6 +869,14 @@ impl BattleState {
             active_entity: None,
             encounter_entity: None,
             notification: None,
+            item_menu: None,
+            use_item: None,
+            roster: None,
+            attack_effect: None,
+            notification_age: Duration::new(0, 0),
+            notification_typed: 0.0,
+            notification_revealed: false,
+            participants: Vec::new(),
         }
     }
     pub fn notifying(&self) -> bool {
@@ -592,12 +884,25 @@ impl BattleState {
     }
     pub fn notify(&mut self, notification: String) {
         self.notification = Some(notification);
+        self.notification_age = Duration::new(0, 0);
+        self.notification_typed = 0.0;
+        self.notification_revealed = false;
+    }
+    pub fn reveal_notification(&mut self) {
+        self.notification_revealed = true;
+    }
+    // How many characters of the notification are showing, or None once it has been typed out.
+    pub fn notification_shown(&self) -> Option<usize> {
+        match self.notification_revealed {
+            true => None,
+            false => Some(self.notification_typed as usize),
+        }
     }
     pub fn clear_notification(&mut self) {
         self.notification = None;
     }
     pub fn animating(&self) -> bool {
-        self.animating || self.notifying()
+        self.animating || self.notifying() || self.attack_effect.is_some()
     }
     pub fn set_animating(&mut self, animating: bool) {
         self.animating = animating;
@@ -607,11 +912,22 @@ impl BattleState {
             self.activate = true;
         }
     }
+    pub fn want_item(&mut self, item: ItemType) {
+        if self.enemy_attacking == None && !self.animating() {
+            self.use_item = Some(item);
+        }
+    }
     pub fn finish_attack(&mut self) {
         self.activate = false;
         self.enemy_attacking = Some(2);
         self.animating = true;
     }
+    pub fn send_out(&mut self, entity: Entity) {
+        self.active_entity = Some(entity);
+        if !self.participants.contains(&entity) {
+            self.participants.push(entity);
+        }
+    }
     pub fn retreat(&mut self) {
         self.active_entity = None;
         self.retreating = true;
@@ -631,20 +947,31 @@ impl BattleState {
     }
 }
 
+pub const CHASE_RADIUS: u32 = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Behavior {
+    Wander,
+    Patrol,
+    Chase,
+    Flee,
+}
+
 #[derive(Debug, Component, Clone)]
 pub struct Encounter {
     pub spirits: Vec<Spirit>,
     pub period: u32,
     pub duration: Duration,
+    pub home: (u32, u32),
+    pub temperament: Behavior,
 }
 
 impl Encounter {
-    fn encounter_size(level: u32) -> Vec<(u32, u32)> {
-        let mut rng = thread_rng();
-        let mut spirit_level = (rng.gen::<f32>() * (level as f32 + 1.0)) as u32;
-        if spirit_level > 2 {
-            spirit_level = 2;
-        }
+    fn encounter_size<R: Rng>(level: u32, rng: &mut R) -> Vec<(u32, u32)> {
+        let spirit_level = cmp::min(
+            (rng.gen::<f32>() * (level as f32 + 1.0)) as u32,
+            MAX_LEVEL,
+        );
         let mut remaining = 11;
         let mut spirit_counts = Vec::new();
         for spirit_level in (0..(spirit_level + 1)).rev() {
@@ -658,16 +985,17 @@ impl Encounter {
         }
         spirit_counts
     }
-    fn random_encounter(level: u32) -> Vec<Spirit> {
+    fn random_encounter<R: Rng>(level: u32, difficulty: &Difficulty, rng: &mut R) -> Vec<Spirit> {
         let mut spirits = Vec::new();
-        let sizes = Encounter::encounter_size(level);
-        match (thread_rng().gen::<f32>() * 5.0) as u32 {
+        let sizes = Encounter::encounter_size(level, rng);
+        match (rng.gen::<f32>() * 5.0) as u32 {
             0 => {
                 for (spirit_level, count) in sizes.iter() {
                     for _ in 0..*count {
                         spirits.push(Spirit::new(
                             SpiritType::Fire(*spirit_level),
                             false,
+                            rng,
                         ));
                     }
                 }
@@ -678,6 +1006,7 @@ impl Encounter {
                         spirits.push(Spirit::new(
                             SpiritType::Water(*spirit_level),
                             false,
+                            rng,
                         ));
                     }
                 }
@@ -688,6 +1017,7 @@ impl Encounter {
                         spirits.push(Spirit::new(
                             SpiritType::Slime(*spirit_level),
                             false,
+                            rng,
                         ));
                     }
                 }
@@ -698,6 +1028,7 @@ impl Encounter {
                         spirits.push(Spirit::new(
                             SpiritType::Light(*spirit_level),
                             false,
+                            rng,
                         ));
                     }
                 }
@@ -708,21 +1039,59 @@ impl Encounter {
                         spirits.push(Spirit::new(
                             SpiritType::Dark(*spirit_level),
                             false,
+                            rng,
                         ));
                     }
                 }
             },
         }
+        for spirit in spirits.iter_mut() {
+            difficulty.strengthen(spirit);
+        }
         spirits
     }
-    pub fn new(level: u32) -> Self {
-        let mut rng = thread_rng();
-        let spirits = Encounter::random_encounter(level);
+    pub fn new<R: Rng>(level: u32, home: (u32, u32), difficulty: &Difficulty, rng: &mut R) -> Self {
+        let spirits = Encounter::random_encounter(level, difficulty, rng);
         let period = 250000000 * (1.0 + rng.gen::<f32>() * 4.0) as u32;
+        let temperament = if rng.gen_bool(0.5) {
+            Behavior::Patrol
+        } else {
+            Behavior::Wander
+        };
         Encounter {
             spirits,
             period,
             duration: Duration::new(0, period),
+            home,
+            temperament,
+        }
+    }
+    pub fn strength(&self) -> u32 {
+        party_strength(&self.spirits)
+    }
+    pub fn leader(&self) -> Option<&Spirit> {
+        self.spirits.iter().max_by_key(|spirit| spirit.level())
+    }
+    pub fn danger(&self, player_strength: u32) -> u32 {
+        let strength = self.strength();
+        if player_strength > strength * 2 {
+            0
+        } else if strength * 4 < player_strength * 3 {
+            1
+        } else if strength < player_strength * 2 {
+            2
+        } else {
+            3
+        }
+    }
+    pub fn behavior(&self, player_strength: u32, distance: u32) -> Behavior {
+        if distance > CHASE_RADIUS {
+            return self.temperament.clone();
+        }
+        match self.danger(player_strength) {
+            0 => Behavior::Flee,
+            1 => self.temperament.clone(),
+            _ => Behavior::Chase,
         }
     }
     pub fn update(&mut self, delta: Duration) -> bool {
@@ -739,10 +1108,55 @@ impl Encounter {
     }
 }
 
+pub const BATTLE_LOG_SIZE: usize = 100;
+pub const AUTO_ADVANCE_MILLIS: u64 = 1500;
+
+// Every battle message of the run, oldest first. Scrolling counts entries back from the newest.
+#[derive(Default, Clone, Debug)]
+pub struct BattleLog {
+    pub entries: Vec<String>,
+    pub open: bool,
+    pub scroll: usize,
+}
+
+impl BattleLog {
+    pub fn push(&mut self, entry: String) {
+        self.entries.push(entry);
+        if self.entries.len() > BATTLE_LOG_SIZE {
+            self.entries.remove(0);
+        }
+        self.scroll = 0;
+    }
+    // Logs the message and shows it until it is dismissed.
+    pub fn notify(&mut self, battle_state: &mut BattleState, notification: String) {
+        self.push(notification.clone());
+        battle_state.notify(notification);
+    }
+    pub fn scroll_back(&mut self) -> bool {
+        if self.scroll + 1 < self.entries.len() {
+            self.scroll += 1;
+            true
+        } else {
+            false
+        }
+    }
+    pub fn scroll_forward(&mut self) -> bool {
+        if self.scroll > 0 {
+            self.scroll -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+const CAMERA_EASE: f32 = 8.0;
+
+// The camera's position is in tiles and may sit between them while it eases after the player.
 #[derive(Default)]
 pub struct Camera {
-    pub x_offset: u32,
-    pub y_offset: u32,
+    pub x: f32,
+    pub y: f32,
     pub width: u32,
     pub height: u32,
 }
@@ -750,17 +1164,92 @@ pub struct Camera {
 impl Camera {
     pub fn new(screen_width: u32, screen_height: u32) -> Self {
         Camera {
-            x_offset: 0,
-            y_offset: 0,
+            x: 0.0,
+            y: 0.0,
             width: screen_width / TILE_SIZE + 2,
             height: screen_height / TILE_SIZE + 2,
         }
     }
+    pub fn x_offset(&self) -> u32 {
+        self.x.max(0.0) as u32
+    }
+    pub fn y_offset(&self) -> u32 {
+        self.y.max(0.0) as u32
+    }
+    pub fn visible(&self, location: (u32, u32)) -> bool {
+        location.0 >= self.x_offset()
+            && location.1 >= self.y_offset()
+            && location.0 < self.x_offset() + self.width
+            && location.1 < self.y_offset() + self.height
+    }
+    pub fn to_screen(&self, position: (f32, f32)) -> (i32, i32) {
+        (
+            ((position.0 - self.x) * TILE_SIZE as f32).round() as i32,
+            ((position.1 - self.y) * TILE_SIZE as f32).round() as i32,
+        )
+    }
+    // Centres the player, without showing anything past the edges of the level.
+    pub fn target(&self, position: (f32, f32), bounds: (u32, u32)) -> (f32, f32) {
+        let view = (
+            SCREEN_SIZE.0 as f32 / TILE_SIZE as f32,
+            SCREEN_SIZE.1 as f32 / TILE_SIZE as f32,
+        );
+        let clamp = |centre: f32, view: f32, bound: u32| {
+            (centre + 0.5 - view / 2.0).min(bound as f32 - view).max(0.0)
+        };
+        (clamp(position.0, view.0, bounds.0), clamp(position.1, view.1, bounds.1))
+    }
+    // Jumps straight to far away targets, like the entrance of a new floor.
+    pub fn ease_towards(&mut self, target: (f32, f32), delta: Duration) {
+        let (dx, dy) = (target.0 - self.x, target.1 - self.y);
+        if dx.abs() > self.width as f32 || dy.abs() > self.height as f32 {
+            self.x = target.0;
+            self.y = target.1;
+        } else {
+            let step = (duration_millis(delta) / 1000.0 * CAMERA_EASE).min(1.0);
+            self.x += dx * step;
+            self.y += dy * step;
+        }
+    }
 }
 
+pub const MOVE_MILLIS: u64 = 120;
+
 #[derive(Debug, Component)]
 pub struct WorldEntity {
     pub location: (u32, u32),
+    pub from: (u32, u32),
+    pub tween: Duration,
+}
+
+impl WorldEntity {
+    pub fn new(location: (u32, u32)) -> Self {
+        WorldEntity {
+            location,
+            from: location,
+            tween: Duration::new(0, 0),
+        }
+    }
+    pub fn move_to(&mut self, location: (u32, u32)) {
+        self.from = self.location;
+        self.location = location;
+        self.tween = Duration::from_millis(MOVE_MILLIS);
+    }
+    pub fn moving(&self) -> bool {
+        self.tween > Duration::new(0, 0)
+    }
+    pub fn update(&mut self, delta: Duration) {
+        self.tween = self.tween.checked_sub(delta).unwrap_or(Duration::new(0, 0));
+    }
+    // Where the entity is drawn, in tiles, eased from the tile it left to the one it is moving to.
+    pub fn position(&self) -> (f32, f32) {
+        let t = 1.0 - (duration_millis(self.tween) / MOVE_MILLIS as f32).min(1.0);
+        let eased = t * (2.0 - t);
+        (
+            self.from.0 as f32 + (self.location.0 as f32 - self.from.0 as f32) * eased,
+            self.from.1 as f32 + (self.location.1 as f32 - self.from.1 as f32) * eased,
+        )
+    }
 }
 
 pub struct Tile {
@@ -778,6 +1267,7 @@ pub struct Room {
 
 pub struct Level {
     pub depth: u32,
+    pub seed: u64,
     pub tiles: HashMap<(u32, u32), Tile>,
     pub rooms: HashMap<(u32, u32), Room>,
     pub entrance: (u32, u32),
@@ -813,8 +1303,47 @@ fn unvisited_neighbors(location: &(u32, u32), rooms: &HashMap<(u32, u32), Room>)
     return unvisited;
 }
 
-fn gen_maze(size: (u32, u32)) -> HashMap<(u32, u32), Room> {
-    let mut rng = thread_rng();
+const SPAWN_SALT: u64 = 0x5eed_5eed_5eed_5eed;
+
+// Each floor gets its own generator so a seed always produces the same dungeon.
+fn level_rng(seed: u64, depth: u32) -> StdRng {
+    let mixed = seed ^ (depth as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let mut bytes = [0u8; 32];
+    for (idx, byte) in bytes.iter_mut().enumerate() {
+        *byte = (mixed >> ((idx % 8) * 8)) as u8 ^ idx as u8;
+    }
+    StdRng::from_seed(bytes)
+}
+
+pub fn today() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86400)
+        .unwrap_or(0)
+}
+
+pub fn daily_seed(day: u64) -> u64 {
+    day.wrapping_mul(0x2545_f491_4f6c_dd1d)
+}
+
+pub fn random_seed() -> u64 {
+    thread_rng().gen()
+}
+
+// Converts days since the epoch into a year-month-day date.
+pub fn date_text(day: u64) -> String {
+    let z = day as i64 + 719468;
+    let era = z / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+fn gen_maze<R: Rng>(size: (u32, u32), rng: &mut R) -> HashMap<(u32, u32), Room> {
     let mut rooms = HashMap::new();
     for y in 0..size.1 {
         for x in 0..size.0 {
@@ -911,21 +1440,156 @@ pub struct Stair {
     pub depth: u32,
 }
 
+pub const POTION_HEAL: u32 = 20;
+pub const TONIC_DEFENSE: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemType {
+    Potion,
+    Elixir,
+    DefenseTonic,
+    CaptureCharm,
+    Revive,
+    Catalyst,
+    Key,
+}
+
+impl ItemType {
+    fn random_treasure<R: Rng>(rng: &mut R) -> Self {
+        let roll = rng.gen::<f32>();
+        if roll < 0.3 {
+            ItemType::Potion
+        } else if roll < 0.4 {
+            ItemType::Elixir
+        } else if roll < 0.55 {
+            ItemType::DefenseTonic
+        } else if roll < 0.7 {
+            ItemType::CaptureCharm
+        } else if roll < 0.85 {
+            ItemType::Revive
+        } else {
+            ItemType::Catalyst
+        }
+    }
+    pub fn usable_in_battle(&self) -> bool {
+        match self {
+            ItemType::Catalyst | ItemType::Key => false,
+            _ => true,
+        }
+    }
+    pub fn usable_in_world(&self) -> bool {
+        match self {
+            ItemType::Potion | ItemType::Elixir | ItemType::Revive => true,
+            _ => false,
+        }
+    }
+    pub fn use_on_spirit(&self, spirit: &mut Spirit) -> bool {
+        if *self == ItemType::Revive || !self.usable_in_world() || spirit.health >= spirit.max_health {
+            return false;
+        }
+        match self {
+            ItemType::Potion => {
+                spirit.health = cmp::min(spirit.max_health, spirit.health + POTION_HEAL);
+            },
+            _ => {
+                spirit.health = spirit.max_health;
+            },
+        }
+        true
+    }
+}
+
+pub fn capture_chance(spirit: &Spirit) -> f64 {
+    1.0 - 0.75 * spirit.health as f64 / spirit.max_health as f64
+}
+
+pub fn defeated_capture_chance(element: &SpiritType) -> f64 {
+    (0.9 - 0.2 * element.level() as f64).max(0.3)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CaptureChoice {
+    Leave,
+    Attempt,
+    Charm,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Pickup {
+    pub item: ItemType,
+}
+
+#[derive(Component, Debug)]
+pub struct Door;
+
+#[derive(Component, Debug)]
+pub struct Shrine {
+    pub used: bool,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Bag {
+    pub items: Vec<(ItemType, u32)>,
+}
+
+impl Bag {
+    pub fn new() -> Self {
+        Bag {
+            items: Vec::new(),
+        }
+    }
+    pub fn count(&self, item: &ItemType) -> u32 {
+        for (held, count) in self.items.iter() {
+            if held == item {
+                return *count;
+            }
+        }
+        0
+    }
+    pub fn add(&mut self, item: ItemType) {
+        for entry in self.items.iter_mut() {
+            if entry.0 == item {
+                entry.1 += 1;
+                return;
+            }
+        }
+        self.items.push((item, 1));
+    }
+    pub fn take(&mut self, item: &ItemType) -> bool {
+        let mut taken = false;
+        for entry in self.items.iter_mut() {
+            if entry.0 == *item && entry.1 > 0 {
+                entry.1 -= 1;
+                taken = true;
+            }
+        }
+        self.items.retain(|(_, count)| *count > 0);
+        taken
+    }
+    pub fn battle_items(&self) -> Vec<(ItemType, u32)> {
+        self.items.iter()
+            .filter(|(item, _)| item.usable_in_battle())
+            .cloned()
+            .collect()
+    }
+}
+
 const ROOM_SIZE: u32 = 5;
 impl Level {
-    pub fn new(depth: u32) -> Self {
-        let mut rng = thread_rng();
+    pub fn new(depth: u32, seed: u64) -> Self {
+        let mut rng = level_rng(seed, depth);
         let entrance = (0, 0);
         let size = (5, 5);
         let exit =(
             size.0 - (rng.gen::<f32>() * 2.0) as u32 - 1,
             size.1 - (rng.gen::<f32>() * 2.0) as u32 - 1,
         );
-        let rooms = gen_maze(size);
+        let rooms = gen_maze(size, &mut rng);
         println!("{:?}", rooms);
         let tiles = tiles_for_rooms(ROOM_SIZE, &rooms);
         Level {
             depth,
+            seed,
             tiles,
             rooms,
             entrance,
@@ -934,14 +1598,20 @@ impl Level {
     }
 
     pub fn spawn_encounters(&self, world: &mut World) {
+        let shrine_chance = world.read_resource::<Settings>().shrine_chance;
+        let difficulty = world.read_resource::<Difficulty>().clone();
         type ClearData<'a> = (
             Entities<'a>,
             WriteStorage<'a, WorldEntity>,
             WriteStorage<'a, Player>,
+            WriteStorage<'a, Bag>,
             WriteStorage<'a, Stair>,
             ReadStorage<'a, Spirit>,
+            WriteExpect<'a, Bestiary>,
         );
-        world.exec(|(entities, mut world_entities, mut player, mut stairs, spirits): ClearData| {
+        // Everything spawned comes from the floor's seed, so a shared seed means a shared run.
+        let mut rng = level_rng(self.seed ^ SPAWN_SALT, self.depth);
+        world.exec(|(entities, mut world_entities, mut player, mut bags, mut stairs, spirits, mut bestiary): ClearData| {
             for (entity, world_entity, ()) in (&*entities, &world_entities, !&player).join() {
                 (*entities).delete(entity);
             }
@@ -953,53 +1623,134 @@ impl Level {
                     (*entities).delete(entity);
                 }
                 let mut spirits = Vec::new();
-                spirits.push(Spirit::new(SpiritType::Fire(0), true));
-                spirits.push(Spirit::new(SpiritType::Water(0), true));
-                spirits.push(Spirit::new(SpiritType::Slime(0), true));
+                spirits.push(Spirit::new(SpiritType::Fire(0), true, &mut rng));
+                spirits.push(Spirit::new(SpiritType::Water(0), true, &mut rng));
+                spirits.push(Spirit::new(SpiritType::Slime(0), true, &mut rng));
+                for spirit in spirits.iter() {
+                    bestiary.own(&spirit.element);
+                }
                 entities.build_entity()
-                    .with(WorldEntity { location: (2, 2) }, &mut world_entities)
-                    .with(Player { spirits: spirits }, &mut player)
+                    .with(WorldEntity::new((2, 2)), &mut world_entities)
+                    .with(Player::new(spirits), &mut player)
+                    .with(Bag::new(), &mut bags)
                     .build();
             } else {
                 for (entity, world_entity, _player) in (&*entities, &mut world_entities, &player).join() {
                     let ex = self.entrance.0 * ROOM_SIZE + (ROOM_SIZE / 2);
                     let ey = self.entrance.1 * ROOM_SIZE + (ROOM_SIZE / 2);
-                    world_entity.location = (ex, ey);
+                    *world_entity = WorldEntity::new((ex, ey));
                 }
             }
             let sx = self.exit.0 * ROOM_SIZE + (ROOM_SIZE / 2);
             let sy = self.exit.1 * ROOM_SIZE + (ROOM_SIZE / 2);
             entities.build_entity()
-                .with(WorldEntity {
-                    location: (sx, sy),
-                }, &mut world_entities)
+                .with(WorldEntity::new((sx, sy)), &mut world_entities)
                 .with(Stair { depth: self.depth + 1 }, &mut stairs)
                 .build();
         });
-        let mut rng = thread_rng();
-        for ((x, y), _room) in self.rooms.iter() {
+        let mut room_keys: Vec<(u32, u32)> = self.rooms.keys().cloned().collect();
+        room_keys.sort();
+        let sx = self.exit.0 * ROOM_SIZE + (ROOM_SIZE / 2);
+        let sy = self.exit.1 * ROOM_SIZE + (ROOM_SIZE / 2);
+        let locked = self.depth > 0 && rng.gen_bool(0.5);
+        let mut key_placed = false;
+        let shrine_room = if rng.gen_bool(shrine_chance) {
+            rng.choose(&room_keys).cloned()
+        } else {
+            None
+        };
+        if let Some((x, y)) = shrine_room {
+            world.create_entity()
+                .with(WorldEntity::new((x * ROOM_SIZE + ROOM_SIZE - 2, y * ROOM_SIZE + ROOM_SIZE - 2)))
+                .with(Shrine { used: false })
+                .build();
+        }
+        if locked {
+            world.create_entity()
+                .with(WorldEntity::new((sx, sy)))
+                .with(Door)
+                .build();
+        }
+        for (x, y) in room_keys.iter() {
+            if (*x, *y) == self.entrance || (*x, *y) == self.exit {
+                continue;
+            }
+            let corner = (x * ROOM_SIZE + 1, y * ROOM_SIZE + 1);
+            if locked && !key_placed {
+                world.create_entity()
+                    .with(WorldEntity::new(corner))
+                    .with(Pickup { item: ItemType::Key })
+                    .build();
+                key_placed = true;
+            } else if rng.gen_bool(0.25) {
+                world.create_entity()
+                    .with(WorldEntity::new(corner))
+                    .with(Pickup { item: ItemType::random_treasure(&mut rng) })
+                    .build();
+            }
+        }
+        for (x, y) in room_keys.iter() {
             if (*x, *y) != self.entrance && (*x, *y) != self.exit {
                 let odds = cmp::min(8, self.depth + 4);
                 if rng.gen_bool(odds as f64 / 10.0) {
                     let tx = x * ROOM_SIZE + (ROOM_SIZE / 2);
                     let ty = y * ROOM_SIZE + (ROOM_SIZE / 2);
                     world.create_entity()
-                        .with(WorldEntity {
-                            location: (tx, ty),
-                        })
-                        .with(Encounter::new(self.depth))
+                        .with(WorldEntity::new((tx, ty)))
+                        .with(Encounter::new(self.depth, (*x, *y), &difficulty, &mut rng))
                         .build();
                 }
             }
         }
     }
 
+    // The size of the level in tiles.
+    pub fn bounds(&self) -> (u32, u32) {
+        self.tiles.keys().fold((0, 0), |(width, height), (x, y)| {
+            (width.max(x + 1), height.max(y + 1))
+        })
+    }
+
     pub fn has_tile(&self, loc: (u32, u32)) -> bool {
         match self.tiles.get(&loc) {
             Some(tile) => tile.active,
             _ => false,
         }
     }
+
+    pub fn room_of(&self, loc: (u32, u32)) -> (u32, u32) {
+        (loc.0 / ROOM_SIZE, loc.1 / ROOM_SIZE)
+    }
+
+    pub fn path_step(&self, from: (u32, u32), to: (u32, u32), max_steps: u32) -> Option<Direction> {
+        let mut first_steps: HashMap<(u32, u32), Direction> = HashMap::new();
+        let mut frontier = VecDeque::new();
+        frontier.push_back((from, 0));
+        while let Some((loc, steps)) = frontier.pop_front() {
+            if loc == to {
+                return first_steps.get(&loc).cloned();
+            }
+            if steps >= max_steps {
+                continue;
+            }
+            for direction in DIRECTIONS.iter() {
+                if let Some(next) = move_in_level(loc, direction, self) {
+                    if next != from && !first_steps.contains_key(&next) {
+                        let first = first_steps.get(&loc).cloned().unwrap_or(direction.clone());
+                        first_steps.insert(next, first);
+                        frontier.push_back((next, steps + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+pub fn distance(a: (u32, u32), b: (u32, u32)) -> u32 {
+    let dx = if a.0 > b.0 { a.0 - b.0 } else { b.0 - a.0 };
+    let dy = if a.1 > b.1 { a.1 - b.1 } else { b.1 - a.1 };
+    dx + dy
 }
 
 fn move_by(loc: (u32, u32), direction: &Direction) -> Option<(u32, u32)> {
@@ -1050,8 +1801,26 @@ pub enum PlayState {
         captured: Vec<SpiritType>,
         lost: Vec<SpiritType>,
     },
+    Capturing {
+        candidates: Vec<SpiritType>,
+        choices: Vec<CaptureChoice>,
+        index: usize,
+    },
     Stairs(u32),
     MainMenu(u32),
+    Inspecting(Entity),
+    Items {
+        index: usize,
+        // The screen the bag was opened from, to go back to when it closes.
+        opened_from: Box<PlayState>,
+    },
+    Settings(usize),
+    Fusing(usize),
+    Details(usize),
+    Naming(usize),
+    Bestiary(usize),
+    Leaderboard(bool),
+    NewGame(usize),
 }
 
 #[derive(Clone, PartialEq)]
@@ -1060,46 +1829,411 @@ pub enum InputState {
     Move(Direction),
     Select,
     Escape,
+    Inspect,
+    Items,
+    Toggle,
+    Transfer,
+    Release,
+    Lock,
+    Sort,
+    Erase,
+    Typed(String),
+}
+
+pub const BESTIARY_FILE: &str = "/bestiary.ron";
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Bestiary {
+    pub seen: Vec<SpiritType>,
+    pub owned: Vec<SpiritType>,
+    #[serde(skip)]
+    pub dirty: bool,
+}
+
+impl Bestiary {
+    pub fn see(&mut self, element: &SpiritType) {
+        if !self.seen.contains(element) {
+            self.seen.push(element.clone());
+            self.dirty = true;
+        }
+    }
+    pub fn own(&mut self, element: &SpiritType) {
+        self.see(element);
+        if !self.owned.contains(element) {
+            self.owned.push(element.clone());
+            self.dirty = true;
+        }
+    }
+    pub fn has_seen(&self, element: &SpiritType) -> bool {
+        self.seen.contains(element)
+    }
+    pub fn has_owned(&self, element: &SpiritType) -> bool {
+        self.owned.contains(element)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Casual,
+    Normal,
+    Hard,
+}
+
+pub const DIFFICULTIES: [Difficulty; 3] = [Difficulty::Casual, Difficulty::Normal, Difficulty::Hard];
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Normal
+    }
+}
+
+impl Difficulty {
+    pub fn revives_at_stairs(&self) -> bool {
+        *self == Difficulty::Casual
+    }
+    pub fn permadeath(&self) -> bool {
+        *self == Difficulty::Hard
+    }
+    pub fn enemy_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Hard => 1.5,
+            _ => 1.0,
+        }
+    }
+    pub fn fusion_cost(&self, required: u32) -> u32 {
+        match self {
+            Difficulty::Hard => (required * 3 + 1) / 2,
+            _ => required,
+        }
+    }
+    pub fn strengthen(&self, spirit: &mut Spirit) {
+        let multiplier = self.enemy_multiplier();
+        spirit.max_health = (spirit.max_health as f32 * multiplier) as u32;
+        spirit.health = spirit.max_health;
+        spirit.attack = (spirit.attack as f32 * multiplier) as u32;
+    }
+}
+
+pub const HIGH_SCORE_FILE: &str = "/high_scores.ron";
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct RunStats {
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub daily: bool,
+    #[serde(default)]
+    pub day: u64,
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default)]
+    pub party: Vec<SpiritType>,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    pub depth: u32,
+    pub battles_won: u32,
+    pub spirits_captured: u32,
+    pub spirits_lost: u32,
+    pub fusions: u32,
+    pub damage_dealt: u32,
+    pub turns: u32,
+    #[serde(skip)]
+    pub recorded: bool,
+}
+
+impl RunStats {
+    pub fn new(settings: &Settings, difficulty: Difficulty) -> Self {
+        let day = today();
+        RunStats {
+            difficulty,
+            seed: match settings.daily_run {
+                true => daily_seed(day),
+                false => random_seed(),
+            },
+            daily: settings.daily_run,
+            day,
+            ..RunStats::default()
+        }
+    }
+    pub fn calculate_score(&self) -> u32 {
+        self.depth * 100
+            + self.battles_won * 10
+            + self.spirits_captured * 5
+            + self.fusions * 20
+            + self.damage_dealt / 10
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub runs: Vec<RunStats>,
+}
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+impl HighScores {
+    pub fn board(&self, daily: bool) -> Vec<&RunStats> {
+        let mut board: Vec<&RunStats> = self.runs.iter()
+            .filter(|run| run.daily == daily)
+            .filter(|run| !daily || run.day == today())
+            .collect();
+        board.sort_by(|a, b| b.score.cmp(&a.score));
+        board.truncate(LEADERBOARD_SIZE);
+        board
+    }
+}
+
+pub const INVENTORY_PAGE_SIZE: usize = 25;
+pub const MAX_NICKNAME_LENGTH: usize = 12;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SortMode {
+    Unsorted,
+    Element,
+    Level,
+    Health,
+    Ratings,
+}
+
+impl SortMode {
+    pub fn next(&self) -> SortMode {
+        match self {
+            SortMode::Unsorted => SortMode::Element,
+            SortMode::Element => SortMode::Level,
+            SortMode::Level => SortMode::Health,
+            SortMode::Health => SortMode::Ratings,
+            SortMode::Ratings => SortMode::Element,
+        }
+    }
+    pub fn sort(&self, spirits: &mut Vec<Spirit>) {
+        match self {
+            SortMode::Unsorted => {},
+            SortMode::Element => spirits.sort_by_key(|spirit| {
+                (spirit.element.element_index(), cmp::Reverse(spirit.level()))
+            }),
+            SortMode::Level => spirits.sort_by_key(|spirit| {
+                (cmp::Reverse(spirit.level()), spirit.element.element_index())
+            }),
+            SortMode::Health => spirits.sort_by_key(|spirit| cmp::Reverse(spirit.health)),
+            SortMode::Ratings => spirits.sort_by_key(|spirit| cmp::Reverse(spirit.ratings())),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct InventoryState {
     pub index: usize,
+    pub viewing_storage: bool,
+    pub sort_mode: SortMode,
+    pub releasing: Option<usize>,
+    pub name_entry: String,
 }
 
 impl InventoryState {
     pub fn new() -> Self {
         InventoryState {
             index: 0,
+            viewing_storage: false,
+            sort_mode: SortMode::Unsorted,
+            releasing: None,
+            name_entry: String::new(),
         }
     }
+    pub fn page(&self) -> usize {
+        self.index / INVENTORY_PAGE_SIZE
+    }
 }
 pub fn can_upgrade(element: &SpiritType) -> bool {
-    match element {
-        SpiritType::Fire(2) => false,
-        SpiritType::Water(2) => false,
-        SpiritType::Slime(2) => false,
-        SpiritType::Light(2) => false,
-        SpiritType::Dark(2) => false,
-        _ => true,
-    }
-}
-pub fn required_spirits(element: &SpiritType) -> u32 {
-    match element {
-        SpiritType::Fire(level) => {
-            4 + level * 2
-        },
-        SpiritType::Water(level) => {
-            4 + level * 2
-        },
-        SpiritType::Slime(level) => {
-            2 + level * 1
+    element.level() < MAX_LEVEL
+}
+pub fn required_spirits(element: &SpiritType, difficulty: &Difficulty) -> u32 {
+    let required = match tier(element) {
+        Some(tier) => tier.required_spirits,
+        None => 0,
+    };
+    difficulty.fusion_cost(required)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Inheritance {
+    Keep,
+    Best,
+    Average,
+}
+
+pub struct FusionPlan {
+    pub consumed: Vec<usize>,
+    pub catalyst: bool,
+    pub result: Spirit,
+}
+
+pub fn plan_fusion(
+    spirits: &Vec<Spirit>,
+    catalysts: u32,
+    index: usize,
+    inheritance: &Inheritance,
+    difficulty: &Difficulty,
+) -> Option<FusionPlan> {
+    let spirit = spirits.get(index)?;
+    if !can_upgrade(&spirit.element) {
+        return None;
+    }
+    let mut consumed = vec![index];
+    for (idx, other) in spirits.iter().enumerate() {
+        if idx != index && other.element == spirit.element && !other.locked {
+            consumed.push(idx);
+        }
+    }
+    let mut used = required_spirits(&spirit.element, difficulty) as usize;
+    let catalyst = consumed.len() + 1 == used && consumed.len() > 1 && catalysts > 0;
+    if catalyst {
+        used -= 1;
+    }
+    if consumed.len() < used {
+        return None;
+    }
+    consumed.truncate(used);
+    let donors: Vec<&Spirit> = consumed.iter().map(|idx| &spirits[*idx]).collect();
+    let mut result = next_spirit(spirit.clone());
+    match inheritance {
+        Inheritance::Keep => {},
+        Inheritance::Best => {
+            result.attack = donors.iter().map(|donor| donor.attack).max().unwrap_or(result.attack);
+            result.stamina = donors.iter().map(|donor| donor.stamina).max().unwrap_or(result.stamina);
+            result.base_defense = donors.iter().map(|donor| donor.base_defense).max().unwrap_or(result.base_defense);
         },
-        SpiritType::Light(level) => {
-            3 + level * 4
+        Inheritance::Average => {
+            let count = donors.len() as u32;
+            result.attack = donors.iter().map(|donor| donor.attack).sum::<u32>() / count;
+            result.stamina = donors.iter().map(|donor| donor.stamina).sum::<u32>() / count;
+            result.base_defense = donors.iter().map(|donor| donor.base_defense).sum::<i32>() / count as i32;
         },
-        SpiritType::Dark(level) => {
-            3 + level * 4
+    }
+    result.defense = result.base_defense;
+    Some(FusionPlan {
+        consumed,
+        catalyst,
+        result,
+    })
+}
+
+const SHRINE_CHANCES: [f64; 4] = [0.0, 0.25, 0.5, 1.0];
+const PARTY_SIZES: [usize; 6] = [10, 15, 20, 25, 35, 50];
+const INHERITANCES: [Inheritance; 3] = [Inheritance::Keep, Inheritance::Best, Inheritance::Average];
+const TEXT_SPEEDS: [TextSpeed; 4] = [TextSpeed::Slow, TextSpeed::Normal, TextSpeed::Fast, TextSpeed::Instant];
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextSpeed {
+    Slow,
+    Normal,
+    Fast,
+    Instant,
+}
+
+impl TextSpeed {
+    // How many characters of a battle message appear each second, or None to show it at once.
+    pub fn chars_per_second(&self) -> Option<f32> {
+        match self {
+            TextSpeed::Slow => Some(20.0),
+            TextSpeed::Normal => Some(45.0),
+            TextSpeed::Fast => Some(90.0),
+            TextSpeed::Instant => None,
+        }
+    }
+}
+
+pub struct Settings {
+    pub heal_on_stairs: bool,
+    pub shrine_chance: f64,
+    pub revive_at_rest: bool,
+    pub auto_capture: bool,
+    pub party_size: usize,
+    pub inheritance: Inheritance,
+    pub daily_run: bool,
+    pub fullscreen: bool,
+    pub auto_advance: bool,
+    pub text_speed: TextSpeed,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            heal_on_stairs: true,
+            shrine_chance: 0.25,
+            revive_at_rest: false,
+            auto_capture: true,
+            party_size: 25,
+            inheritance: Inheritance::Best,
+            daily_run: false,
+            fullscreen: false,
+            auto_advance: false,
+            text_speed: TextSpeed::Normal,
+        }
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    match value {
+        true => "Yes",
+        false => "No",
+    }.to_string()
+}
+
+fn cycle<T: PartialEq + Clone>(options: &[T], current: &T, forward: bool) -> T {
+    let index = options.iter().position(|option| option == current).unwrap_or(0);
+    let next = match forward {
+        true => (index + 1) % options.len(),
+        false => (index + options.len() - 1) % options.len(),
+    };
+    options[next].clone()
+}
+
+impl Settings {
+    pub fn options(&self) -> Vec<(String, String)> {
+        vec![
+            ("Full heal on stairs".to_string(), yes_no(self.heal_on_stairs)),
+            ("Shrine chance".to_string(), format!("{}%", (self.shrine_chance * 100.0) as u32)),
+            ("Revive fainted at rest".to_string(), yes_no(self.revive_at_rest)),
+            ("Capture defeated spirits".to_string(), match self.auto_capture {
+                true => "Automatically",
+                false => "By choice",
+            }.to_string()),
+            ("Party size".to_string(), format!("{}", self.party_size)),
+            ("Fusion inherits".to_string(), match self.inheritance {
+                Inheritance::Keep => "Selected spirit's stats",
+                Inheritance::Best => "Best stats",
+                Inheritance::Average => "Average stats",
+            }.to_string()),
+            ("Dungeon".to_string(), match self.daily_run {
+                true => "Daily",
+                false => "Random",
+            }.to_string()),
+            ("Fullscreen".to_string(), yes_no(self.fullscreen)),
+            ("Battle messages".to_string(), match self.auto_advance {
+                true => "Advance automatically",
+                false => "Wait for a key",
+            }.to_string()),
+            ("Text speed".to_string(), match self.text_speed {
+                TextSpeed::Slow => "Slow",
+                TextSpeed::Normal => "Normal",
+                TextSpeed::Fast => "Fast",
+                TextSpeed::Instant => "Instant",
+            }.to_string()),
+        ]
+    }
+    pub fn change(&mut self, index: usize, forward: bool) {
+        match index {
+            0 => self.heal_on_stairs = !self.heal_on_stairs,
+            1 => self.shrine_chance = cycle(&SHRINE_CHANCES, &self.shrine_chance, forward),
+            2 => self.revive_at_rest = !self.revive_at_rest,
+            3 => self.auto_capture = !self.auto_capture,
+            4 => self.party_size = cycle(&PARTY_SIZES, &self.party_size, forward),
+            5 => self.inheritance = cycle(&INHERITANCES, &self.inheritance, forward),
+            6 => self.daily_run = !self.daily_run,
+            7 => self.fullscreen = !self.fullscreen,
+            8 => self.auto_advance = !self.auto_advance,
+            9 => self.text_speed = cycle(&TEXT_SPEEDS, &self.text_speed, forward),
+            _ => {},
         }
     }
 }
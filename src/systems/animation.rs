@@ -0,0 +1,66 @@
+use specs::*;
+use state::*;
+use std::time::Duration;
+
+pub struct TweenMovement;
+impl<'a> System<'a> for TweenMovement {
+    type SystemData = (
+        WriteStorage<'a, WorldEntity>,
+        Read<'a, Duration>,
+    );
+
+    fn run(&mut self, (mut world_entities, delta_time): Self::SystemData) {
+        for world_entity in (&mut world_entities).join() {
+            world_entity.update(*delta_time);
+        }
+    }
+}
+
+pub struct AnimateSprites;
+impl<'a> System<'a> for AnimateSprites {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, Spirit>,
+        WriteStorage<'a, Animation>,
+        Read<'a, Duration>,
+        Write<'a, BattleState>,
+        Write<'a, FloatingNumbers>,
+    );
+
+    fn run(&mut self, (entities, spirits, mut animations, delta_time, mut battle_state, mut floating_numbers): Self::SystemData) {
+        floating_numbers.update(*delta_time);
+        let mut missing = Vec::new();
+        for (entity, spirit, ()) in (&*entities, &spirits, !&animations).join() {
+            missing.push((entity, spirit.health));
+        }
+        for (entity, health) in missing {
+            animations.insert(entity, Animation::new(health, entity.id() as usize));
+        }
+        if let Some(ref mut effect) = battle_state.attack_effect {
+            if !effect.started {
+                if let Some(animation) = animations.get_mut(effect.attacker) {
+                    animation.play(AnimationKind::Lunge);
+                }
+                effect.started = true;
+            }
+            effect.elapsed += *delta_time;
+        }
+        if battle_state.attack_effect.as_ref().map_or(false, |effect| effect.finished()) {
+            battle_state.attack_effect = None;
+        }
+        for (spirit, animation) in (&spirits, &mut animations).join() {
+            if spirit.health == 0 && animation.kind != AnimationKind::Faint {
+                animation.play(AnimationKind::Faint);
+            } else if spirit.health > 0 && animation.kind == AnimationKind::Faint {
+                animation.play(AnimationKind::Idle);
+            } else if spirit.health < animation.last_health && animation.kind != AnimationKind::Hit {
+                animation.play(AnimationKind::Hit);
+            }
+            animation.last_health = spirit.health;
+            animation.update(*delta_time);
+            if animation.finished && animation.kind != AnimationKind::Faint {
+                animation.play(AnimationKind::Idle);
+            }
+        }
+    }
+}
@@ -2,30 +2,124 @@ use ggez::*;
 use specs::*;
 use state::*;
 use input::*;
+use render::*;
 use std::time::Duration;
 use rand::*;
 
+fn wander(location: (u32, u32), level: &Level) -> Option<(u32, u32)> {
+    let mut rng = thread_rng();
+    match rng.choose(&DIRECTIONS) {
+        Some(direction) => move_in_level(location, direction, level),
+        None => None,
+    }
+}
+
+fn flee(location: (u32, u32), player_loc: (u32, u32), level: &Level) -> Option<(u32, u32)> {
+    let mut best = None;
+    let mut best_distance = distance(location, player_loc);
+    for direction in DIRECTIONS.iter() {
+        if let Some(moved) = move_in_level(location, direction, level) {
+            if distance(moved, player_loc) > best_distance {
+                best_distance = distance(moved, player_loc);
+                best = Some(moved);
+            }
+        }
+    }
+    best
+}
+
 pub struct WanderEncounters;
 impl<'a> System<'a> for WanderEncounters {
     type SystemData = (
+        ReadExpect<'a, PlayState>,
         WriteStorage<'a, Encounter>,
         WriteStorage<'a, WorldEntity>,
+        ReadStorage<'a, Player>,
         ReadExpect<'a, Level>,
         ReadExpect<'a, Duration>,
     );
-    fn run(&mut self, (mut encounters, mut world_entities, level, delta_time): Self::SystemData) {
-        let mut rng = thread_rng();
+    fn run(&mut self, (play_state, mut encounters, mut world_entities, players, level, delta_time): Self::SystemData) {
+        if *play_state != PlayState::InWorld {
+            return;
+        }
+        let mut player_loc = None;
+        let mut player_strength = 0;
+        for (world_entity, player) in (&world_entities, &players).join() {
+            player_loc = Some(world_entity.location);
+            player_strength = party_strength(&player.spirits);
+        }
         for (encounter, world_entity) in (&mut encounters, &mut world_entities).join() {
             if encounter.update(*delta_time) {
-                let direction = rng.choose(&[
-                    Direction::Up,
-                    Direction::Right,
-                    Direction::Down,
-                    Direction::Left,
-                ]);
-                if let Some(direction) = direction {
-                    if let Some(moved) = move_in_level(world_entity.location, direction, &level) {
-                        world_entity.location = moved;
+                let location = world_entity.location;
+                let moved = match player_loc {
+                    Some(player_loc) => {
+                        match encounter.behavior(player_strength, distance(location, player_loc)) {
+                            Behavior::Wander => wander(location, &level),
+                            Behavior::Patrol => {
+                                wander(location, &level)
+                                    .filter(|moved| level.room_of(*moved) == encounter.home)
+                            },
+                            Behavior::Chase => {
+                                level.path_step(location, player_loc, CHASE_RADIUS * 2)
+                                    .and_then(|direction| move_in_level(location, &direction, &level))
+                            },
+                            Behavior::Flee => flee(location, player_loc, &level),
+                        }
+                    },
+                    None => wander(location, &level),
+                };
+                if let Some(moved) = moved {
+                    world_entity.move_to(moved);
+                }
+            }
+        }
+    }
+}
+
+pub struct FindPickups;
+impl<'a> System<'a> for FindPickups {
+    type SystemData = (
+        ReadExpect<'a, PlayState>,
+        Entities<'a>,
+        ReadStorage<'a, WorldEntity>,
+        ReadStorage<'a, Pickup>,
+        ReadStorage<'a, Player>,
+        WriteStorage<'a, Bag>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (play_state, entities, world_entities, pickups, players, mut bags, sounds): Self::SystemData) {
+        if *play_state == PlayState::InWorld {
+            for (player_loc, _player, bag) in (&world_entities, &players, &mut bags).join() {
+                for (entity, world_entity, pickup) in (&*entities, &world_entities, &pickups).join() {
+                    if world_entity.location == player_loc.location {
+                        bag.add(pickup.item.clone());
+                        (*entities).delete(entity);
+                        sounds.play(&sounds.confirm);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct VisitShrines;
+impl<'a> System<'a> for VisitShrines {
+    type SystemData = (
+        ReadExpect<'a, PlayState>,
+        ReadExpect<'a, Settings>,
+        ReadStorage<'a, WorldEntity>,
+        WriteStorage<'a, Shrine>,
+        WriteStorage<'a, Player>,
+        ReadExpect<'a, Sounds>,
+    );
+    fn run(&mut self, (play_state, settings, world_entities, mut shrines, mut players, sounds): Self::SystemData) {
+        if *play_state == PlayState::InWorld {
+            for (player_loc, player) in (&world_entities, &mut players).join() {
+                for (world_entity, shrine) in (&world_entities, &mut shrines).join() {
+                    if world_entity.location == player_loc.location && !shrine.used {
+                        player.rest(settings.revive_at_rest, settings.party_size);
+                        shrine.used = true;
+                        sounds.play(&sounds.collide);
                     }
                 }
             }
@@ -42,12 +136,17 @@ impl<'a> System<'a> for FindEncounters {
         ReadStorage<'a, WorldEntity>,
         ReadStorage<'a, Encounter>,
         ReadStorage<'a, Stair>,
-        ReadStorage<'a, Player>,
+        WriteStorage<'a, Player>,
         WriteStorage<'a, Spirit>,
         WriteStorage<'a, PlayerSpirit>,
+        WriteExpect<'a, Bestiary>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Difficulty>,
         ReadExpect<'a, Sounds>,
+        WriteExpect<'a, InputState>,
+        Write<'a, BattleLog>,
     );
-    fn run(&mut self, (mut play_state, mut battle_state, entities, world_entities, encounters, stairs, player_store, mut spirits, mut player_spirits, sounds): Self::SystemData) {
+    fn run(&mut self, (mut play_state, mut battle_state, entities, world_entities, encounters, stairs, mut player_store, mut spirits, mut player_spirits, mut bestiary, settings, difficulty, sounds, mut input_state, mut battle_log): Self::SystemData) {
         if *play_state == PlayState::InWorld {
             let mut player_loc = (0, 0);
             let mut player = None;
@@ -59,13 +158,23 @@ impl<'a> System<'a> for FindEncounters {
                 for (entity, world_entity, stairs) in (&*entities, &world_entities, &stairs).join() {
                     if world_entity.location == player_loc {
                         *play_state = PlayState::Stairs(stairs.depth);
+                        *input_state = InputState::Rest;
+                        for player in (&mut player_store).join() {
+                            if settings.heal_on_stairs {
+                                player.rest(settings.revive_at_rest || difficulty.revives_at_stairs(), settings.party_size);
+                            } else if difficulty.revives_at_stairs() {
+                                player.revive_all(settings.party_size);
+                            }
+                        }
                     }
                 }
                 for (entity, world_entity, encounter) in (&*entities, &world_entities, &encounters).join() {
                     if world_entity.location == player_loc {
                         battle_state.encounter_entity = Some(entity);
                         *play_state = PlayState::InBattle;
+                        *input_state = InputState::Rest;
                         for spirit in encounter.spirits.clone() {
+                            bestiary.see(&spirit.element);
                             (*entities).build_entity()
                                 .with(spirit, &mut spirits)
                                 .build();
@@ -78,14 +187,19 @@ impl<'a> System<'a> for FindEncounters {
                                 .build();
                             if active {
                                 battle_state.combat_move = Some(0);
-                                battle_state.active_entity = Some(entity);
+                                battle_state.participants.clear();
+                                battle_state.send_out(entity);
                                 battle_state.in_combat = true;
                                 battle_state.activate = false;
                                 battle_state.enemy_attacking = None;
                                 battle_state.notification = None;
+                                battle_state.roster = None;
+                                battle_state.attack_effect = None;
                             }
                             active = false;
                         }
+                        battle_log.push(encounter_text(encounter.spirits.len()));
+                        battle_log.open = false;
                         sounds.play(&sounds.encounter);
                     }
                 }
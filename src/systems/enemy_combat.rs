@@ -6,6 +6,8 @@ use render::*;
 
 fn act<'a>(
     battle_state: &mut BattleState,
+    battle_log: &mut BattleLog,
+    floating_numbers: &mut FloatingNumbers,
     spirit: &Spirit,
     player_spirit: &Spirit,
     self_entity: &Entity,
@@ -18,39 +20,45 @@ fn act<'a>(
         match action.effect {
             MoveType::DamageOne(amount) => {
                 let amount = action.effect.actual_amount(spirit, player_spirit);
-                updater.insert(
-                    *player_entity,
-                    CombatEffects::new(vec![CombatEffect::Damage(amount), CombatEffect::ShedDefense(1)])
-                );
+                let effects = vec![CombatEffect::Damage(amount), CombatEffect::ShedDefense(1)];
+                floating_numbers.spawn(*player_entity, &effects);
+                updater.insert(*player_entity, CombatEffects::new(effects));
                 sounds.sound_for_attack(spirit);
-                battle_state.notify(damage_one_text(&action, &spirit, &player_spirit, amount, true));
+                battle_state.attack_effect = Some(AttackEffect::new(
+                    spirit.element.clone(),
+                    *self_entity,
+                    vec![*player_entity],
+                ));
+                battle_log.notify(battle_state, damage_one_text(&action, &spirit, &player_spirit, amount, true));
             },
             MoveType::DamageMany(amount) => {
                 let amount = action.effect.actual_amount(spirit, player_spirit);
-                updater.insert(
-                    *player_entity,
-                    CombatEffects::new(vec![CombatEffect::Damage(amount)])
-                );
+                let effects = vec![CombatEffect::Damage(amount)];
+                floating_numbers.spawn(*player_entity, &effects);
+                updater.insert(*player_entity, CombatEffects::new(effects));
                 sounds.sound_for_attack(spirit);
-                battle_state.notify(damage_one_text(&action, &spirit, &player_spirit, amount, true));
+                battle_state.attack_effect = Some(AttackEffect::new(
+                    spirit.element.clone(),
+                    *self_entity,
+                    vec![*player_entity],
+                ));
+                battle_log.notify(battle_state, damage_one_text(&action, &spirit, &player_spirit, amount, true));
             },
             MoveType::Heal(amount) => {
                 println!("{:?}", *self_entity);
                 println!("{:?}", *spirit);
                 let amount = action.effect.actual_amount(spirit, spirit);
-                updater.insert(
-                    *self_entity,
-                    CombatEffects::new(vec![CombatEffect::Heal(amount)])
-                );
-                battle_state.notify(heal_text(&action, &spirit, amount));
+                let effects = vec![CombatEffect::Heal(amount)];
+                floating_numbers.spawn(*self_entity, &effects);
+                updater.insert(*self_entity, CombatEffects::new(effects));
+                battle_log.notify(battle_state, heal_text(&action, &spirit, amount));
             },
             MoveType::Defend(amount) => {
                 let amount = action.effect.actual_amount(spirit, spirit);
-                updater.insert(
-                    *self_entity,
-                    CombatEffects::new(vec![CombatEffect::Defense(amount)])
-                );
-                battle_state.notify(defense_text(&action, &spirit, amount));
+                let effects = vec![CombatEffect::Defense(amount)];
+                floating_numbers.spawn(*self_entity, &effects);
+                updater.insert(*self_entity, CombatEffects::new(effects));
+                battle_log.notify(battle_state, defense_text(&action, &spirit, amount));
             },
         }
     }
@@ -60,6 +68,8 @@ pub struct EnemyCombat;
 impl<'a> System<'a> for EnemyCombat {
     type SystemData = (
         Write<'a, BattleState>,
+        Write<'a, BattleLog>,
+        Write<'a, FloatingNumbers>,
         Entities<'a>,
         ReadStorage<'a, Spirit>,
         ReadStorage<'a, PlayerSpirit>,
@@ -68,7 +78,7 @@ impl<'a> System<'a> for EnemyCombat {
         WriteExpect<'a, InputState>,
     );
 
-    fn run(&mut self, (mut battle_state, entities, spirits, player_spirits, mut updater, sounds, mut input_state): Self::SystemData) {
+    fn run(&mut self, (mut battle_state, mut battle_log, mut floating_numbers, entities, spirits, player_spirits, mut updater, sounds, mut input_state): Self::SystemData) {
         if !battle_state.animating() && !battle_state.retreating {
             if let Some(attacking) = battle_state.enemy_attacking {
                 println!("{}", attacking);
@@ -92,7 +102,7 @@ impl<'a> System<'a> for EnemyCombat {
                         }
                         for (idx, (entity, spirit)) in attacking_spirits.iter().enumerate() {
                             if idx as u32 == attacking {
-                                act(&mut battle_state, spirit, player_spirit, &entity, &player_entity, &mut updater, &sounds);
+                                act(&mut battle_state, &mut battle_log, &mut floating_numbers, spirit, player_spirit, &entity, &player_entity, &mut updater, &sounds);
                                 *input_state = InputState::Rest;
                             }
                         }
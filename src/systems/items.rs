@@ -0,0 +1,104 @@
+use specs::*;
+use state::*;
+use render::*;
+use rand::*;
+
+pub struct WatchItems;
+impl<'a> System<'a> for WatchItems {
+    type SystemData = (
+        Write<'a, BattleState>,
+        Write<'a, BattleLog>,
+        Entities<'a>,
+        WriteStorage<'a, Spirit>,
+        WriteStorage<'a, PlayerSpirit>,
+        WriteStorage<'a, CombatEffects>,
+        WriteStorage<'a, Bag>,
+        ReadExpect<'a, Level>,
+        WriteExpect<'a, Bestiary>,
+        WriteExpect<'a, RunStats>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Sounds>,
+    );
+
+    fn run(&mut self, (mut battle_state, mut battle_log, entities, mut spirits, mut player_spirits, mut combat_effects, mut bags, level, mut bestiary, mut run_stats, settings, sounds): Self::SystemData) {
+        let item = match battle_state.use_item.take() {
+            Some(item) => item,
+            None => return,
+        };
+        let mut active = None;
+        let mut fainted = None;
+        let mut party_size = 0;
+        for (entity, spirit, player_spirit) in (&*entities, &spirits, &player_spirits).join() {
+            if player_spirit.active && spirit.health > 0 {
+                active = Some((entity, spirit.clone()));
+            } else if spirit.health == 0 && fainted.is_none() {
+                fainted = Some((entity, spirit.clone()));
+            }
+            party_size += 1;
+        }
+        let mut enemy = None;
+        for (entity, spirit, ()) in (&*entities, &spirits, !&player_spirits).join() {
+            if spirit.health > 0 && enemy.is_none() {
+                enemy = Some((entity, spirit.clone()));
+            }
+        }
+        let target = match item {
+            ItemType::Potion | ItemType::Elixir | ItemType::DefenseTonic => active,
+            ItemType::Revive => fainted,
+            ItemType::CaptureCharm => enemy,
+            _ => None,
+        };
+        let (entity, spirit) = match target {
+            Some(target) => target,
+            None => {
+                battle_log.notify(&mut battle_state, item_used_text(&item, None, false));
+                return;
+            },
+        };
+        let mut taken = false;
+        for bag in (&mut bags).join() {
+            if bag.take(&item) {
+                taken = true;
+            }
+        }
+        if !taken {
+            return;
+        }
+        let success = match item {
+            ItemType::Potion => {
+                combat_effects.insert(entity, CombatEffects::new(vec![CombatEffect::Heal(POTION_HEAL)]));
+                true
+            },
+            ItemType::Elixir => {
+                combat_effects.insert(entity, CombatEffects::new(vec![CombatEffect::Heal(spirit.max_health)]));
+                true
+            },
+            ItemType::DefenseTonic => {
+                combat_effects.insert(entity, CombatEffects::new(vec![CombatEffect::Defense(TONIC_DEFENSE)]));
+                true
+            },
+            ItemType::Revive => {
+                combat_effects.insert(entity, CombatEffects::new(vec![CombatEffect::Heal(spirit.max_health / 2)]));
+                true
+            },
+            ItemType::CaptureCharm => {
+                let captured = party_size < settings.party_size && thread_rng().gen_bool(capture_chance(&spirit));
+                if captured {
+                    bestiary.own(&spirit.element);
+                    run_stats.spirits_captured += 1;
+                    (*entities).delete(entity);
+                    (*entities).build_entity()
+                        .with(Spirit::captured(spirit.element.clone(), level.depth), &mut spirits)
+                        .with(PlayerSpirit { active: false }, &mut player_spirits)
+                        .build();
+                    sounds.play(&sounds.collide);
+                }
+                captured
+            },
+            _ => false,
+        };
+        run_stats.turns += 1;
+        battle_log.notify(&mut battle_state, item_used_text(&item, Some(&spirit.element), success));
+        battle_state.finish_attack();
+    }
+}
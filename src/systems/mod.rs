@@ -1,7 +1,13 @@
 mod encounters;
 mod moves;
 mod enemy_combat;
+mod items;
+mod animation;
+mod notifications;
 
 pub use self::encounters::*;
 pub use self::moves::*;
 pub use self::enemy_combat::*;
+pub use self::items::*;
+pub use self::animation::*;
+pub use self::notifications::*;
@@ -1,6 +1,7 @@
 use ggez::*;
 use specs::*;
 use state::*;
+use render::*;
 use std::time::Duration;
 
 fn get_active_enemies<'a>(
@@ -36,60 +37,93 @@ pub struct WatchAttack;
 impl<'a> System<'a> for WatchAttack {
     type SystemData = (
         Write<'a, BattleState>,
+        Write<'a, BattleLog>,
         Entities<'a>,
         WriteStorage<'a, Spirit>,
         ReadStorage<'a, PlayerSpirit>,
         WriteStorage<'a, CombatEffects>,
+        Write<'a, FloatingNumbers>,
+        WriteExpect<'a, RunStats>,
         ReadExpect<'a, Sounds>,
     );
 
-    fn run(&mut self, (mut battle_state, mut entities, mut spirits, player_spirits, mut combat_effects, sounds): Self::SystemData) {
+    fn run(&mut self, (mut battle_state, mut battle_log, mut entities, mut spirits, player_spirits, mut combat_effects, mut floating_numbers, mut run_stats, sounds): Self::SystemData) {
         if battle_state.activate {
             let my_move = battle_state.get_move(&spirits);
             if let (Some(player), Some(my_move))
                 = (get_active_ally(&entities, &spirits, &player_spirits), my_move) {
+                let mut dealt = 0;
                 if let Some(player_spirit) = spirits.get(player) {
                     match my_move.effect {
                         MoveType::DamageMany(amount) => {
                             let affected = get_active_enemies(
-                                3,
+                                my_move.effect.enemy_targets(),
                                 &entities,
                                 &spirits,
                                 &player_spirits,
                             );
+                            battle_state.attack_effect = Some(AttackEffect::new(
+                                player_spirit.element.clone(),
+                                player,
+                                affected.iter().map(|(entity, _enemy)| *entity).collect(),
+                            ));
                             for (entity, enemy) in affected.iter() {
                                 let amount = my_move.effect.actual_amount(&player_spirit, enemy);
-                                combat_effects.insert(*entity, CombatEffects::new(vec![CombatEffect::Damage(amount)]));
+                                dealt += amount;
+                                battle_log.push(damage_one_text(&my_move, &player_spirit, enemy, amount, false));
+                                let effects = vec![CombatEffect::Damage(amount)];
+                                floating_numbers.spawn(*entity, &effects);
+                                combat_effects.insert(*entity, CombatEffects::new(effects));
                             }
                             sounds.sound_for_attack(player_spirit);
                             battle_state.finish_attack();
                         },
                         MoveType::DamageOne(amount) => {
                             let affected = get_active_enemies(
-                                1,
+                                my_move.effect.enemy_targets(),
                                 &entities,
                                 &spirits,
                                 &player_spirits,
                             );
+                            battle_state.attack_effect = Some(AttackEffect::new(
+                                player_spirit.element.clone(),
+                                player,
+                                affected.iter().map(|(entity, _enemy)| *entity).collect(),
+                            ));
                             for (entity, enemy) in affected.iter() {
                                 let amount = my_move.effect.actual_amount(&player_spirit, enemy);
-                                combat_effects.insert(*entity, CombatEffects::new(vec![CombatEffect::Damage(amount), CombatEffect::ShedDefense(1)]));
+                                dealt += amount;
+                                battle_log.push(damage_one_text(&my_move, &player_spirit, enemy, amount, false));
+                                let effects = vec![CombatEffect::Damage(amount), CombatEffect::ShedDefense(1)];
+                                floating_numbers.spawn(*entity, &effects);
+                                combat_effects.insert(*entity, CombatEffects::new(effects));
                             }
                             sounds.sound_for_attack(player_spirit);
                             battle_state.finish_attack();
                         },
                         MoveType::Heal(amount) => {
                             let amount = my_move.effect.actual_amount(&player_spirit, &player_spirit);
-                            combat_effects.insert(player, CombatEffects::new(vec![CombatEffect::Heal(amount)]));
+                            battle_log.push(heal_text(&my_move, &player_spirit, amount));
+                            let effects = vec![CombatEffect::Heal(amount)];
+                            floating_numbers.spawn(player, &effects);
+                            combat_effects.insert(player, CombatEffects::new(effects));
                             battle_state.finish_attack();
                         },
                         MoveType::Defend(amount) => {
                             let amount = my_move.effect.actual_amount(&player_spirit, &player_spirit);
-                            combat_effects.insert(player, CombatEffects::new(vec![CombatEffect::Defense(amount)]));
+                            battle_log.push(defense_text(&my_move, &player_spirit, amount));
+                            let effects = vec![CombatEffect::Defense(amount)];
+                            floating_numbers.spawn(player, &effects);
+                            combat_effects.insert(player, CombatEffects::new(effects));
                             battle_state.finish_attack();
                         },
                     }
                 }
+                if let Some(player_spirit) = spirits.get_mut(player) {
+                    player_spirit.damage_dealt += dealt;
+                }
+                run_stats.damage_dealt += dealt;
+                run_stats.turns += 1;
             }
         }
     }
@@ -100,18 +134,24 @@ impl<'a> System<'a> for TickEffects {
     type SystemData = (
         Entities<'a>,
         WriteStorage<'a, Spirit>,
+        ReadStorage<'a, PlayerSpirit>,
         WriteStorage<'a, CombatEffects>,
         Read<'a, Duration>,
         Write<'a, BattleState>,
+        Write<'a, BattleLog>,
         ReadExpect<'a, Sounds>,
     );
 
-    fn run(&mut self, (entities, mut spirits, mut combat_effects, delta_time, mut battle_state, sounds): Self::SystemData) {
+    fn run(&mut self, (entities, mut spirits, player_spirits, mut combat_effects, delta_time, mut battle_state, mut battle_log, sounds): Self::SystemData) {
         let mut completed = Vec::new();
         for (entity, spirit, combat_effect) in (&*entities, &mut spirits, &mut combat_effects).join() {
             if combat_effect.update(*delta_time) {
                 println!("{:?}", combat_effect);
+                let was_alive = spirit.health > 0;
                 combat_effect.apply_tick(spirit);
+                if was_alive && spirit.health == 0 {
+                    battle_log.push(faint_text(spirit, player_spirits.get(entity).is_none()));
+                }
                 sounds.play(&sounds.blip);
             }
             if !combat_effect.active() {
@@ -134,15 +174,21 @@ pub struct WatchSpirits;
 impl<'a> System<'a> for WatchSpirits {
     type SystemData = (
         Write<'a, BattleState>,
+        Write<'a, BattleLog>,
         WriteExpect<'a, PlayState>,
         Entities<'a>,
         ReadStorage<'a, Spirit>,
         WriteStorage<'a, PlayerSpirit>,
         WriteStorage<'a, Player>,
+        ReadExpect<'a, Level>,
+        WriteExpect<'a, Bestiary>,
+        WriteExpect<'a, RunStats>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Difficulty>,
         ReadExpect<'a, Sounds>,
     );
 
-    fn run(&mut self, (mut battle_state, mut play_state, entities, spirits, mut player_spirits, mut players, sounds): Self::SystemData) {
+    fn run(&mut self, (mut battle_state, mut battle_log, mut play_state, entities, spirits, mut player_spirits, mut players, level, mut bestiary, mut run_stats, settings, difficulty, sounds): Self::SystemData) {
         if *play_state == PlayState::InBattle {
             let mut players_alive = false;
             let mut retreating = true;
@@ -164,6 +210,7 @@ impl<'a> System<'a> for WatchSpirits {
             if !battle_state.retreating && retreating {
                 sounds.lose.play();
                 battle_state.retreat();
+                battle_log.open = false;
             }
             let mut enemies_alive = false;
             let mut captured_enemies = Vec::new();
@@ -177,25 +224,48 @@ impl<'a> System<'a> for WatchSpirits {
                 let mut captured = Vec::new();
                 let mut lost = Vec::new();
                 let mut new_spirits = Vec::new();
-                for (spirit, _player_spirit) in (&spirits, &player_spirits).join() {
+                let mut fainted = Vec::new();
+                for (entity, spirit, _player_spirit) in (&*entities, &spirits, &player_spirits).join() {
                     if spirit.health > 0 {
-                        new_spirits.push(spirit.clone());
+                        let mut survivor = spirit.clone();
+                        if battle_state.participants.contains(&entity) {
+                            survivor.wins += 1;
+                        }
+                        new_spirits.push(survivor);
+                    } else {
+                        fainted.push(spirit.clone());
                     }
                 }
                 for player in (&mut players).join() {
-                    for captured_spirit in captured_enemies.iter() {
-                        if player.spirits.len() < 25 {
+                    player.spirits = new_spirits.clone();
+                    if !difficulty.permadeath() {
+                        player.fainted.extend(fainted.iter().cloned());
+                    }
+                    if settings.auto_capture {
+                        for captured_spirit in captured_enemies.iter() {
                             captured.push(captured_spirit.clone());
-                            new_spirits.push(Spirit::new(captured_spirit.clone(), true));
-                        } else {
-                            lost.push(captured_spirit.clone());
+                            bestiary.own(captured_spirit);
+                            player.add_spirit(Spirit::captured(captured_spirit.clone(), level.depth), settings.party_size);
                         }
                     }
-                    player.spirits = new_spirits.clone();
                 }
-                *play_state = PlayState::Looting {
-                    captured,
-                    lost,
+                run_stats.battles_won += 1;
+                run_stats.spirits_captured += captured.len() as u32;
+                run_stats.spirits_lost += lost.len() as u32;
+                if settings.auto_capture {
+                    battle_log.push(auto_capture_text(&captured, &lost));
+                }
+                *play_state = if settings.auto_capture {
+                    PlayState::Looting {
+                        captured,
+                        lost,
+                    }
+                } else {
+                    PlayState::Capturing {
+                        choices: vec![CaptureChoice::Attempt; captured_enemies.len()],
+                        candidates: captured_enemies,
+                        index: 0,
+                    }
                 };
                 battle_state.in_combat = false;
                 if let Some(encounter) = battle_state.encounter_entity {
@@ -0,0 +1,44 @@
+use specs::*;
+use state::*;
+use render::*;
+use std::time::Duration;
+
+// Types battle messages out at the chosen text speed and, when auto advance is on,
+// dismisses them once the attack behind them has played out.
+pub struct AdvanceNotifications;
+impl<'a> System<'a> for AdvanceNotifications {
+    type SystemData = (
+        Write<'a, BattleState>,
+        Read<'a, BattleLog>,
+        ReadExpect<'a, PlayState>,
+        ReadExpect<'a, Settings>,
+        Read<'a, Duration>,
+    );
+
+    fn run(&mut self, (mut battle_state, battle_log, play_state, settings, delta_time): Self::SystemData) {
+        if *play_state != PlayState::InBattle || battle_log.open {
+            return;
+        }
+        let length = match battle_state.notification {
+            Some(ref notification) => plain_text(notification).chars().count(),
+            None => return,
+        };
+        if !battle_state.notification_revealed {
+            match settings.text_speed.chars_per_second() {
+                Some(speed) => battle_state.notification_typed += duration_millis(*delta_time) * speed / 1000.0,
+                None => battle_state.reveal_notification(),
+            }
+            if battle_state.notification_typed >= length as f32 {
+                battle_state.reveal_notification();
+            }
+            return;
+        }
+        if !settings.auto_advance || battle_state.animating || battle_state.attack_effect.is_some() {
+            return;
+        }
+        battle_state.notification_age += *delta_time;
+        if battle_state.notification_age >= Duration::from_millis(AUTO_ADVANCE_MILLIS) {
+            battle_state.clear_notification();
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::cmp;
+use state::SpiritType;
+
+struct NamedTier {
+    name: &'static str,
+    sprite: &'static str,
+    tint: Option<[f32; 4]>,
+    required_spirits: u32,
+}
+
+pub struct Tier {
+    pub name: String,
+    pub sprite: &'static str,
+    pub tint: Option<[f32; 4]>,
+    pub required_spirits: u32,
+}
+
+// Past the named tiers every element keeps fusing into numbered ranks of its last tier,
+// "Primordial Flame II" and so on, up to this level.
+pub const MAX_LEVEL: u32 = 50;
+
+const ASCENDED: Option<[f32; 4]> = Some([1.0, 0.85, 0.4, 1.0]);
+const TRANSCENDENT: Option<[f32; 4]> = Some([1.0, 0.5, 1.0, 1.0]);
+
+static FIRE_TIERS: [NamedTier; 5] = [
+    NamedTier { name: "Ember", sprite: "ember", tint: None, required_spirits: 4 },
+    NamedTier { name: "Fire Elemental", sprite: "fire_elemental", tint: None, required_spirits: 6 },
+    NamedTier { name: "Grand Efreet", sprite: "grand_efreet", tint: None, required_spirits: 8 },
+    NamedTier { name: "Inferno Lord", sprite: "grand_efreet", tint: ASCENDED, required_spirits: 10 },
+    NamedTier { name: "Primordial Flame", sprite: "grand_efreet", tint: TRANSCENDENT, required_spirits: 12 },
+];
+
+static WATER_TIERS: [NamedTier; 5] = [
+    NamedTier { name: "Nymph", sprite: "nymph", tint: None, required_spirits: 4 },
+    NamedTier { name: "Water Maiden", sprite: "water_maiden", tint: None, required_spirits: 6 },
+    NamedTier { name: "Leviathan", sprite: "leviathan", tint: None, required_spirits: 8 },
+    NamedTier { name: "Tidal Sovereign", sprite: "leviathan", tint: ASCENDED, required_spirits: 10 },
+    NamedTier { name: "Abyssal Ocean", sprite: "leviathan", tint: TRANSCENDENT, required_spirits: 12 },
+];
+
+static SLIME_TIERS: [NamedTier; 5] = [
+    NamedTier { name: "Lesser Slime", sprite: "lesser_slime", tint: None, required_spirits: 2 },
+    NamedTier { name: "Slime", sprite: "slime", tint: None, required_spirits: 3 },
+    NamedTier { name: "Greater Slime", sprite: "greater_slime", tint: None, required_spirits: 4 },
+    NamedTier { name: "Slime King", sprite: "greater_slime", tint: ASCENDED, required_spirits: 5 },
+    NamedTier { name: "Endless Ooze", sprite: "greater_slime", tint: TRANSCENDENT, required_spirits: 6 },
+];
+
+static DARK_TIERS: [NamedTier; 5] = [
+    NamedTier { name: "Imp", sprite: "imp", tint: None, required_spirits: 3 },
+    NamedTier { name: "Fiend", sprite: "fiend", tint: None, required_spirits: 7 },
+    NamedTier { name: "War Dominator", sprite: "war_dominator", tint: None, required_spirits: 11 },
+    NamedTier { name: "Archfiend", sprite: "war_dominator", tint: ASCENDED, required_spirits: 15 },
+    NamedTier { name: "Eternal Night", sprite: "war_dominator", tint: TRANSCENDENT, required_spirits: 19 },
+];
+
+static LIGHT_TIERS: [NamedTier; 5] = [
+    NamedTier { name: "Wisp", sprite: "wisp", tint: None, required_spirits: 3 },
+    NamedTier { name: "Guardian", sprite: "guardian", tint: None, required_spirits: 7 },
+    NamedTier { name: "Holy Angel", sprite: "holy_angel", tint: None, required_spirits: 11 },
+    NamedTier { name: "Seraph", sprite: "holy_angel", tint: ASCENDED, required_spirits: 15 },
+    NamedTier { name: "Radiant Sun", sprite: "holy_angel", tint: TRANSCENDENT, required_spirits: 19 },
+];
+
+fn named_tiers(element: &SpiritType) -> &'static [NamedTier] {
+    match element {
+        SpiritType::Fire(_) => &FIRE_TIERS,
+        SpiritType::Water(_) => &WATER_TIERS,
+        SpiritType::Slime(_) => &SLIME_TIERS,
+        SpiritType::Dark(_) => &DARK_TIERS,
+        SpiritType::Light(_) => &LIGHT_TIERS,
+    }
+}
+
+fn roman_numeral(mut value: u32) -> String {
+    let numerals = [
+        (50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+    ];
+    let mut numeral = String::new();
+    for &(amount, letters) in numerals.iter() {
+        while value >= amount {
+            numeral.push_str(letters);
+            value -= amount;
+        }
+    }
+    numeral
+}
+
+pub fn tier(element: &SpiritType) -> Option<Tier> {
+    let level = element.level();
+    if level > MAX_LEVEL {
+        return None;
+    }
+    let named = named_tiers(element);
+    let last = named.len() as u32 - 1;
+    let base = &named[cmp::min(level, last) as usize];
+    let name = match level > last {
+        true => format!("{} {}", base.name, roman_numeral(level - last + 1)),
+        false => base.name.to_string(),
+    };
+    Some(Tier {
+        name,
+        sprite: base.sprite,
+        tint: base.tint,
+        required_spirits: match level < MAX_LEVEL {
+            true => base.required_spirits,
+            false => 0,
+        },
+    })
+}
+
+// How many levels of an element have their own tier before the numbered ranks start.
+pub fn named_levels(element: &SpiritType) -> u32 {
+    named_tiers(element).len() as u32
+}
+
+// The bestiary lists the named tiers. The numbered ranks past them share their last sprite.
+pub fn all_spirit_types() -> Vec<SpiritType> {
+    let elements: [fn(u32) -> SpiritType; 5] = [
+        SpiritType::Fire,
+        SpiritType::Water,
+        SpiritType::Slime,
+        SpiritType::Dark,
+        SpiritType::Light,
+    ];
+    let mut all = Vec::new();
+    for element in elements.iter() {
+        for level in 0..named_levels(&element(0)) {
+            all.push(element(level));
+        }
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::time::Duration;
+    use state::*;
+
+    #[test]
+    fn max_level_spirits_fit() {
+        let elements: [fn(u32) -> SpiritType; 5] = [
+            SpiritType::Fire,
+            SpiritType::Water,
+            SpiritType::Slime,
+            SpiritType::Dark,
+            SpiritType::Light,
+        ];
+        for element in elements.iter() {
+            let enemy = Spirit::new(element(MAX_LEVEL), false, &mut StdRng::from_seed([7; 32]));
+            let captured = Spirit::captured(element(MAX_LEVEL), MAX_LEVEL);
+            assert_eq!(captured.level(), MAX_LEVEL);
+            assert!(captured.max_health < 100_000, "{:?}", captured);
+            assert!(!can_upgrade(&captured.element));
+            let encounter = Encounter {
+                spirits: vec![enemy; 11],
+                period: 0,
+                duration: Duration::new(0, 0),
+                home: (0, 0),
+                temperament: Behavior::Wander,
+            };
+            encounter.danger(party_strength(&vec![captured; 50]));
+        }
+    }
+}